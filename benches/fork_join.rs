@@ -0,0 +1,141 @@
+//! Measures the fork-join speedup of loading the sites and merging their tags.
+//!
+//! For every corpus shape, read strategy and thread count it reports the median wall time of
+//! `load_sites_from` and `get_all_tags`, the throughput of the loading and the speedup against a
+//! single thread. The maximum amount of threads defaults to the available parallelism and can be
+//! changed with the `BENCH_MAX_THREADS` environment variable, and the repetitions of each
+//! measurement with `BENCH_REPETITIONS`.
+
+use std::{
+    fs::remove_dir_all,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use tp1_fork_join_108225::{
+    corpus_generator::{generate_corpus, CorpusConfig},
+    sites_information::{
+        input_selection::InputSelection, line_reader::ReadStrategy,
+        sites_collection::SitesCollection,
+    },
+};
+
+const DEFAULT_REPETITIONS: usize = 5;
+
+/// Represents the shape of a synthetic corpus.
+struct CorpusShape {
+    name: &'static str,
+    sites: u32,
+    questions_per_site: u64,
+}
+
+const SHAPES: [CorpusShape; 3] = [
+    CorpusShape {
+        name: "many_small_files",
+        sites: 200,
+        questions_per_site: 250,
+    },
+    CorpusShape {
+        name: "few_medium_files",
+        sites: 8,
+        questions_per_site: 6_250,
+    },
+    CorpusShape {
+        name: "one_huge_file",
+        sites: 1,
+        questions_per_site: 50_000,
+    },
+];
+
+/// Returns the median of the durations.
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort();
+    durations[durations.len() / 2]
+}
+
+/// Returns the thread counts to measure: the powers of two up to the maximum, and the maximum.
+fn thread_counts(max_threads: u8) -> Vec<u8> {
+    let mut counts: Vec<u8> = (0..8)
+        .map(|power| 1u8 << power)
+        .take_while(|count| *count <= max_threads)
+        .collect();
+    if counts.last() != Some(&max_threads) {
+        counts.push(max_threads);
+    }
+    counts
+}
+
+fn environment_value(name: &str) -> Option<usize> {
+    std::env::var(name).ok()?.parse().ok()
+}
+
+fn main() {
+    let max_threads = environment_value("BENCH_MAX_THREADS")
+        .or_else(|| std::thread::available_parallelism().map(usize::from).ok())
+        .unwrap_or(1)
+        .clamp(1, u8::MAX as usize) as u8;
+    let repetitions = environment_value("BENCH_REPETITIONS")
+        .unwrap_or(DEFAULT_REPETITIONS)
+        .max(1);
+    let root: PathBuf = std::env::temp_dir().join("tp1_fork_join_bench");
+    let strategies = [
+        ("chunked", ReadStrategy::default()),
+        ("line_bridge", ReadStrategy::LineBridge),
+    ];
+
+    println!(
+        "{:<18} {:<12} {:>7} {:>12} {:>10} {:>8} {:>12} {:>8}",
+        "corpus", "strategy", "threads", "load", "MiB/s", "speedup", "all_tags", "speedup"
+    );
+
+    for shape in &SHAPES {
+        let directory = root.join(shape.name);
+        let config = CorpusConfig {
+            sites: shape.sites,
+            questions_per_site: shape.questions_per_site,
+            ..CorpusConfig::default()
+        };
+        let size = generate_corpus(&config, &directory).unwrap().bytes;
+        let selection = InputSelection::new().directory(&directory);
+
+        for (strategy_name, strategy) in strategies {
+            let mut baseline: Option<(Duration, Duration)> = None;
+            for threads in thread_counts(max_threads) {
+                let mut sites = SitesCollection::builder()
+                    .num_threads(threads)
+                    .read_strategy(strategy)
+                    .build()
+                    .unwrap();
+
+                let mut load_times = Vec::new();
+                let mut tags_times = Vec::new();
+                for _ in 0..repetitions {
+                    let start = Instant::now();
+                    sites.load_sites_from(&selection).unwrap();
+                    load_times.push(start.elapsed());
+
+                    let start = Instant::now();
+                    std::hint::black_box(sites.get_all_tags());
+                    tags_times.push(start.elapsed());
+                }
+                let load = median(load_times);
+                let tags = median(tags_times);
+                let (base_load, base_tags) = *baseline.get_or_insert((load, tags));
+
+                println!(
+                    "{:<18} {:<12} {:>7} {:>10.2}ms {:>10.1} {:>7.2}x {:>10.3}ms {:>7.2}x",
+                    shape.name,
+                    strategy_name,
+                    threads,
+                    load.as_secs_f64() * 1000.0,
+                    size as f64 / (1024.0 * 1024.0) / load.as_secs_f64(),
+                    base_load.as_secs_f64() / load.as_secs_f64(),
+                    tags.as_secs_f64() * 1000.0,
+                    base_tags.as_secs_f64() / tags.as_secs_f64(),
+                );
+            }
+        }
+    }
+
+    let _ = remove_dir_all(&root);
+}
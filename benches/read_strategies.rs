@@ -0,0 +1,51 @@
+//! Compares the throughput of the read strategies of a single big file at different thread counts.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tp1_fork_join_108225::{
+    corpus_generator::{generate_corpus, site_file_name, CorpusConfig},
+    sites_information::{
+        input_selection::InputSelection, line_reader::ReadStrategy,
+        sites_collection::SitesCollection,
+    },
+};
+
+const QUESTIONS: u64 = 100_000;
+const THREAD_COUNTS: [u8; 4] = [1, 2, 4, 8];
+
+fn read_strategies(c: &mut Criterion) {
+    let directory = std::env::temp_dir().join("tp1_read_strategies_bench");
+    let config = CorpusConfig {
+        sites: 1,
+        questions_per_site: QUESTIONS,
+        ..CorpusConfig::default()
+    };
+    let size = generate_corpus(&config, &directory).unwrap().bytes;
+    let selection = InputSelection::new().file(directory.join(site_file_name(0)));
+
+    let mut group = c.benchmark_group("read_strategies");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(size));
+
+    let strategies = [
+        ("chunked", ReadStrategy::default()),
+        ("line_bridge", ReadStrategy::LineBridge),
+    ];
+    for (name, strategy) in strategies {
+        for threads in THREAD_COUNTS {
+            let mut sites = SitesCollection::builder()
+                .num_threads(threads)
+                .read_strategy(strategy)
+                .build()
+                .unwrap();
+            group.bench_with_input(BenchmarkId::new(name, threads), &threads, |b, _| {
+                b.iter(|| sites.load_sites_from(&selection).unwrap())
+            });
+        }
+    }
+    group.finish();
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+criterion_group!(benches, read_strategies);
+criterion_main!(benches);
@@ -11,5 +11,4 @@ rm stackoverflow.com-Posts.jsonl.gz
 
 for filename in *.gz; do
     curl -o $filename -L https://huggingface.co/datasets/flax-sentence-embeddings/stackexchange_title_body_jsonl/resolve/main/$filename 
-    gunzip -v $filename
 done
\ No newline at end of file
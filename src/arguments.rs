@@ -0,0 +1,893 @@
+use crate::error_execution::ErrorExecution;
+use tp1_fork_join_108225::sites_information::{
+    export::TableFormat,
+    input_schema::InputSchema,
+    ranking::{Ranking, RankingMetric, RankingOrder},
+    site_id::DuplicateSites,
+    text_layout::TextLayout,
+    tokenizer::TokenizerKind,
+};
+
+const DEFAULT_DATA_PATH: &str = "test_data";
+const DEFAULT_ID: &str = "108225";
+const DEFAULT_TOP_SITES: u8 = 10;
+const DEFAULT_TOP_TAGS: u8 = 10;
+
+const USAGE: &str = "Usage: tp1_fork_join_108225 [OPTIONS] [<number_of_working_threads>]
+
+Options:
+  -d, --data-dir <PATH>   Directory with the .jsonl(.gz) or Posts.xml(.gz) files to analyse, can be repeated (default: test_data)
+  -f, --file <PATH>       File to analyse regardless of the patterns, can be repeated
+  -r, --recursive         Also analyses the files in the subdirectories of the data directories
+      --include <GLOB>    Only analyses the files of the data directories that match the pattern, can be repeated
+      --exclude <GLOB>    Skips the files of the data directories that match the pattern, can be repeated
+  -o, --output <PATH>     File where the JSON report is written, or directory where the tables are written (default: stdout)
+      --format <FORMAT>   Format of the report: json, csv or tsv (default: json)
+  -t, --threads <N>       Number of working threads
+      --top-sites <N>     Amount of chatty sites in the report (default: 10)
+      --top-tags <N>      Amount of chatty tags in the report (default: 10)
+      --min-tag-questions <N>
+                          Minimum amount of questions of a chatty tag (default: 0)
+      --tag-prior-weight <W>
+                          Ranks the chatty tags by the Bayesian average of their ratio, pulling the tags
+                          with few questions toward the ratio of their site with a prior of W questions
+                          (default: 0, no smoothing)
+      --rank <SPEC>       Adds a leaderboard of sites and tags, can be repeated. The spec is
+                          NAME=METRIC[:asc|:desc][:min=N][:top=N][:prior=W], where METRIC is questions,
+                          words, ratio, median_words or co_occurrences (default: desc, min=0, top=10)
+      --related-tags <N>  Includes the N tags that appear together the most with each tag, for each site
+                          and for all the sites (default: 0, not included)
+      --schema <NAME>     Shape of the JSON questions: texts ({\"texts\": [...], \"tags\": [...]}), fields
+                          (title, body, tags and answers fields) or posts (rows of the Posts table of the
+                          StackExchange dumps, only the questions are used) (default: texts). Posts.xml
+                          files are always read as posts
+      --text-layout <FIELDS>
+                          Field held by each text of the questions, comma separated: title, body or other.
+                          Texts beyond the list hold its last field (default: title,body)
+      --tokenizer <NAME>  How the words of the texts are counted: whitespace, unicode (Unicode word
+                          boundaries, without punctuation) or prose (Unicode words without code blocks
+                          and markup) (default: whitespace)
+      --filter <EXPR>     Only analyses the questions that pass the filter, such as
+                          \"tag:rust and not (site:stackoverflow.com or words<10)\". Predicates: tag:<glob>,
+                          site:<glob>, text:<regex>, title:<regex>, body:<regex>, and words, title_words or
+                          body_words compared with <, <=, =, >= or >. Joined with and, or, not and parentheses.
+                          The files of the sites rejected by the site predicates are not read, and those
+                          sites are not reported
+      --state <PATH>      Reuses the state of a previous analysis stored in the file, reading only the
+                          new and changed files, and writes the updated state to it
+      --group-pattern <REGEX>
+                          Assigns the files whose name matches the regular expression to the site
+                          matched by its \"site\" group or by its first group, such as \"(.*)\\.part\\d+\"
+      --site-mapping <PATH>
+                          Assigns the files to the sites listed in a JSON object of file names or paths
+                          to site names. Replaces --group-pattern, and the other way around
+      --duplicates <POLICY>
+                          What is done when files of different directories belong to the same site:
+                          merge or error (default: merge)
+      --id <ID>           Identifier written in the \"padron\" field (default: 108225)
+      --strict            Fails on the first invalid line or file instead of skipping it
+      --diagnostics       Includes the skipped lines and files in the report
+  -h, --help              Prints this message
+  -V, --version           Prints the version of the program";
+
+/// Configuration of a run of the program obtained from the command line.
+///
+/// # Attributes
+///
+/// * `data_dirs` - The directories where the files are located.
+/// * `files` - The files that are analysed regardless of the patterns.
+/// * `recursive` - Whether the subdirectories of the data directories are also browsed.
+/// * `include` - The glob patterns the files of the data directories must match.
+/// * `exclude` - The glob patterns that exclude files of the data directories.
+/// * `output` - The file where the report is written, or the directory where the tables are written. If it is None, the report is printed in stdout.
+/// * `format` - The format of the report.
+/// * `threads` - The number of working threads. If it is None, rayon decides the amount.
+/// * `top_sites` - The number of chatty sites to include in the report.
+/// * `top_tags` - The number of chatty tags to include in the report.
+/// * `min_tag_questions` - The minimum amount of questions of a chatty tag.
+/// * `tag_prior_weight` - The weight of the prior of the Bayesian average of the ratio of the chatty tags.
+/// * `rankings` - The name and the ranking of each additional leaderboard.
+/// * `related_tags` - The number of related tags of each tag to include in the report, none if it is zero.
+/// * `schema` - The shape of the JSON questions of the lines.
+/// * `text_layout` - The field held by each text of the questions.
+/// * `tokenizer` - How the words of the texts are counted.
+/// * `filter` - The expression of the filter of the questions. If it is None, every question is analysed.
+/// * `state` - The file with the state of the previous analysis. If it is None, every file is read and no state is written.
+/// * `grouping` - The rule that assigns the files to their sites. If it is None, every file is its own site.
+/// * `duplicates` - What is done when files of different directories belong to the same site.
+/// * `id` - The identifier written in the report.
+/// * `strict` - Whether the program fails on the first invalid line or file.
+/// * `diagnostics` - Whether the skipped lines and files are included in the report.
+#[derive(Debug, PartialEq)]
+pub struct Arguments {
+    pub data_dirs: Vec<String>,
+    pub files: Vec<String>,
+    pub recursive: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub output: Option<String>,
+    pub format: OutputFormat,
+    pub threads: Option<u8>,
+    pub top_sites: u8,
+    pub top_tags: u8,
+    pub min_tag_questions: u64,
+    pub tag_prior_weight: f64,
+    pub rankings: Vec<(String, Ranking)>,
+    pub related_tags: u8,
+    pub schema: InputSchema,
+    pub text_layout: TextLayout,
+    pub tokenizer: TokenizerKind,
+    pub filter: Option<String>,
+    pub state: Option<String>,
+    pub grouping: Option<GroupingOption>,
+    pub duplicates: DuplicateSites,
+    pub id: String,
+    pub strict: bool,
+    pub diagnostics: bool,
+}
+
+/// Enum that represents the format of the report written by the program.
+///
+/// # Variants
+///
+/// * `Json` - A single JSON document with the whole report.
+/// * `Table` - The tables of sites, tags and tags of each site in the given format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Table(TableFormat),
+}
+
+/// Enum that represents the rule requested to assign the files to their sites.
+///
+/// # Variants
+///
+/// * `Pattern` - A regular expression matched against the file names.
+/// * `Mapping` - The path of a JSON file that maps the files to their sites.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupingOption {
+    Pattern(String),
+    Mapping(String),
+}
+
+/// Enum that represents what the program was asked to do.
+///
+/// # Variants
+///
+/// * `Run` - Analyse the sites with the given arguments, boxed because they are much larger than the other variants.
+/// * `Help` - Print the usage of the program.
+/// * `Version` - Print the version of the program.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Box<Arguments>),
+    Help,
+    Version,
+}
+
+impl Default for Arguments {
+    /// Creates the arguments used when no option is provided.
+    ///
+    /// # Returns
+    ///
+    /// A new Arguments instance.
+    fn default() -> Self {
+        Arguments {
+            data_dirs: vec![DEFAULT_DATA_PATH.to_string()],
+            files: Vec::new(),
+            recursive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            output: None,
+            format: OutputFormat::Json,
+            threads: None,
+            top_sites: DEFAULT_TOP_SITES,
+            top_tags: DEFAULT_TOP_TAGS,
+            min_tag_questions: 0,
+            tag_prior_weight: 0.0,
+            rankings: Vec::new(),
+            related_tags: 0,
+            schema: InputSchema::default(),
+            text_layout: TextLayout::default(),
+            tokenizer: TokenizerKind::default(),
+            filter: None,
+            state: None,
+            grouping: None,
+            duplicates: DuplicateSites::default(),
+            id: DEFAULT_ID.to_string(),
+            strict: false,
+            diagnostics: false,
+        }
+    }
+}
+
+/// Returns the usage message of the program.
+///
+/// # Returns
+///
+/// A string with the usage message.
+pub fn usage() -> &'static str {
+    USAGE
+}
+
+/// Parses the arguments provided to the program, skipping the name of the executable.
+/// If no data directory nor file is provided, the default data directory is used.
+/// If an argument is unknown, is missing its value or has an invalid value, it returns an error.
+///
+/// # Arguments
+///
+/// * `arguments` - A vector of strings containing the arguments provided to the program.
+///
+/// # Returns
+///
+/// A result with the command to execute or an ErrorExecution.
+pub fn parse_arguments(arguments: &[String]) -> Result<Command, ErrorExecution> {
+    let mut parsed = Arguments {
+        data_dirs: Vec::new(),
+        ..Arguments::default()
+    };
+    let mut remaining = arguments.iter().skip(1);
+    // The threads can be given either as the positional argument or as an option, but not both.
+    let mut positional_threads = false;
+
+    while let Some(argument) = remaining.next() {
+        match argument.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-d" | "--data-dir" => parsed
+                .data_dirs
+                .push(obtain_value(argument, remaining.next())?),
+            "-f" | "--file" => parsed.files.push(obtain_value(argument, remaining.next())?),
+            "-r" | "--recursive" => parsed.recursive = true,
+            "--include" => parsed
+                .include
+                .push(obtain_value(argument, remaining.next())?),
+            "--exclude" => parsed
+                .exclude
+                .push(obtain_value(argument, remaining.next())?),
+            "-o" | "--output" => parsed.output = Some(obtain_value(argument, remaining.next())?),
+            "--format" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.format = obtain_format(argument, &value)?;
+            }
+            "-t" | "--threads" => {
+                if positional_threads {
+                    return Err(ErrorExecution::WrongAmountOfParameters);
+                }
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.threads = Some(obtain_number_worker_threads(&value)?);
+            }
+            "--top-sites" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.top_sites = obtain_amount(argument, &value)?;
+            }
+            "--top-tags" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.top_tags = obtain_amount(argument, &value)?;
+            }
+            "--min-tag-questions" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.min_tag_questions = obtain_parsed(argument, &value)?;
+            }
+            "--tag-prior-weight" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.tag_prior_weight = obtain_weight(argument, &value)?;
+            }
+            "--rank" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.rankings.push(obtain_ranking(argument, &value)?);
+            }
+            "--related-tags" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.related_tags = obtain_amount(argument, &value)?;
+            }
+            "--schema" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.schema = obtain_schema(argument, &value)?;
+            }
+            "--text-layout" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.text_layout = obtain_text_layout(argument, &value)?;
+            }
+            "--tokenizer" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.tokenizer = obtain_tokenizer(argument, &value)?;
+            }
+            "--group-pattern" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.grouping = Some(GroupingOption::Pattern(value));
+            }
+            "--site-mapping" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.grouping = Some(GroupingOption::Mapping(value));
+            }
+            "--duplicates" => {
+                let value = obtain_value(argument, remaining.next())?;
+                parsed.duplicates = obtain_duplicate_sites(argument, &value)?;
+            }
+            "--filter" => parsed.filter = Some(obtain_value(argument, remaining.next())?),
+            "--state" => parsed.state = Some(obtain_value(argument, remaining.next())?),
+            "--id" => parsed.id = obtain_value(argument, remaining.next())?,
+            "--strict" => parsed.strict = true,
+            "--diagnostics" => parsed.diagnostics = true,
+            other if other.starts_with('-') => {
+                return Err(ErrorExecution::UnknownArgument(other.to_string()));
+            }
+            other => {
+                if parsed.threads.is_some() {
+                    return Err(ErrorExecution::WrongAmountOfParameters);
+                }
+                parsed.threads = Some(obtain_number_worker_threads(other)?);
+                positional_threads = true;
+            }
+        }
+    }
+
+    if parsed.data_dirs.is_empty() && parsed.files.is_empty() {
+        parsed.data_dirs.push(DEFAULT_DATA_PATH.to_string());
+    }
+
+    Ok(Command::Run(Box::new(parsed)))
+}
+
+/// Returns the value that follows an option. If there is no value, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that expects the value.
+/// * `value` - The argument that follows the option, if any.
+///
+/// # Returns
+///
+/// A result with the value of the option.
+fn obtain_value(option: &str, value: Option<&String>) -> Result<String, ErrorExecution> {
+    match value {
+        Some(value) => Ok(value.clone()),
+        None => Err(ErrorExecution::MissingArgumentValue(option.to_string())),
+    }
+}
+
+/// Returns the number of threads to be used by the program.
+/// If the number is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `number_of_threads` - A string containing the number of threads to be used by the program.
+///
+/// # Returns
+///
+/// A result with the number of threads to be used by the program.
+fn obtain_number_worker_threads(number_of_threads: &str) -> Result<u8, ErrorExecution> {
+    match number_of_threads.parse::<u8>() {
+        Ok(number) => Ok(number),
+        Err(_) => Err(ErrorExecution::InvalidNumberOfThreads),
+    }
+}
+
+/// Returns the amount of elements requested by a top-N option.
+/// If the amount is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the amount.
+/// * `amount` - A string containing the amount.
+///
+/// # Returns
+///
+/// A result with the amount.
+fn obtain_amount(option: &str, amount: &str) -> Result<u8, ErrorExecution> {
+    match amount.parse::<u8>() {
+        Ok(number) => Ok(number),
+        Err(_) => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            amount.to_string(),
+        )),
+    }
+}
+
+/// Returns the format of the report requested by the format option.
+/// If the format is unknown, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the format.
+/// * `format` - A string containing the format.
+///
+/// # Returns
+///
+/// A result with the format.
+fn obtain_format(option: &str, format: &str) -> Result<OutputFormat, ErrorExecution> {
+    match format {
+        "json" => Ok(OutputFormat::Json),
+        "csv" => Ok(OutputFormat::Table(TableFormat::Csv)),
+        "tsv" => Ok(OutputFormat::Table(TableFormat::Tsv)),
+        _ => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            format.to_string(),
+        )),
+    }
+}
+
+/// Returns the layout of the texts of the questions requested by the text layout option.
+/// If a field is unknown, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the layout.
+/// * `layout` - A string containing the comma separated fields.
+///
+/// # Returns
+///
+/// A result with the layout.
+fn obtain_text_layout(option: &str, layout: &str) -> Result<TextLayout, ErrorExecution> {
+    match TextLayout::from_spec(layout) {
+        Some(text_layout) => Ok(text_layout),
+        None => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            layout.to_string(),
+        )),
+    }
+}
+
+/// Returns the tokenizer requested by the tokenizer option.
+/// If the tokenizer is unknown, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the tokenizer.
+/// * `tokenizer` - A string containing the name of the tokenizer.
+///
+/// # Returns
+///
+/// A result with the kind of tokenizer.
+fn obtain_tokenizer(option: &str, tokenizer: &str) -> Result<TokenizerKind, ErrorExecution> {
+    match TokenizerKind::from_name(tokenizer) {
+        Some(kind) => Ok(kind),
+        None => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            tokenizer.to_string(),
+        )),
+    }
+}
+
+/// Returns the schema requested by the schema option.
+/// If the schema is unknown, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the schema.
+/// * `schema` - A string containing the name of the schema.
+///
+/// # Returns
+///
+/// A result with the schema of the questions.
+fn obtain_schema(option: &str, schema: &str) -> Result<InputSchema, ErrorExecution> {
+    match InputSchema::from_name(schema) {
+        Some(schema) => Ok(schema),
+        None => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            schema.to_string(),
+        )),
+    }
+}
+
+/// Returns the policy for duplicate sites requested by the duplicates option.
+/// If the policy is unknown, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the policy.
+/// * `policy` - A string containing the name of the policy.
+///
+/// # Returns
+///
+/// A result with the policy for duplicate sites.
+fn obtain_duplicate_sites(option: &str, policy: &str) -> Result<DuplicateSites, ErrorExecution> {
+    match DuplicateSites::from_name(policy) {
+        Some(duplicates) => Ok(duplicates),
+        None => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            policy.to_string(),
+        )),
+    }
+}
+
+/// Returns the value of an option parsed as a number. If it is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the value.
+/// * `value` - A string containing the value.
+///
+/// # Returns
+///
+/// A result with the parsed value.
+fn obtain_parsed<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, ErrorExecution> {
+    match value.parse::<T>() {
+        Ok(parsed) => Ok(parsed),
+        Err(_) => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+/// Returns the weight of a prior, which must be a finite number that is not negative.
+/// If the weight is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the weight.
+/// * `weight` - A string containing the weight.
+///
+/// # Returns
+///
+/// A result with the weight.
+fn obtain_weight(option: &str, weight: &str) -> Result<f64, ErrorExecution> {
+    match obtain_parsed::<f64>(option, weight)? {
+        parsed if parsed.is_finite() && parsed >= 0.0 => Ok(parsed),
+        _ => Err(ErrorExecution::InvalidArgumentValue(
+            option.to_string(),
+            weight.to_string(),
+        )),
+    }
+}
+
+/// Returns the name and the ranking of a leaderboard written as
+/// `NAME=METRIC[:asc|:desc][:min=N][:top=N][:prior=W]`. If the spec is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `option` - The option that received the spec.
+/// * `spec` - A string containing the spec of the leaderboard.
+///
+/// # Returns
+///
+/// A result with the name and the ranking of the leaderboard.
+fn obtain_ranking(option: &str, spec: &str) -> Result<(String, Ranking), ErrorExecution> {
+    let invalid = || ErrorExecution::InvalidArgumentValue(option.to_string(), spec.to_string());
+    let (name, description) = spec.split_once('=').ok_or_else(invalid)?;
+    let mut parts = description.split(':');
+    let metric = parts
+        .next()
+        .and_then(RankingMetric::from_name)
+        .ok_or_else(invalid)?;
+    if name.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut ranking = Ranking::new(metric);
+    for part in parts {
+        ranking = match part.split_once('=') {
+            None if part == "asc" => ranking.order(RankingOrder::Ascending),
+            None if part == "desc" => ranking.order(RankingOrder::Descending),
+            Some(("min", value)) => ranking.min_questions(value.parse().map_err(|_| invalid())?),
+            Some(("top", value)) => ranking.limit(value.parse().map_err(|_| invalid())?),
+            Some(("prior", value)) => ranking.prior_weight(obtain_weight(option, value)?),
+            _ => return Err(invalid()),
+        };
+    }
+    Ok((name.to_string(), ranking))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_arguments(arguments: &[&str]) -> Vec<String> {
+        std::iter::once("program")
+            .chain(arguments.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn no_arguments_use_defaults() {
+        let command = parse_arguments(&to_arguments(&[])).unwrap();
+        assert_eq!(command, Command::Run(Box::default()));
+    }
+
+    #[test]
+    fn positional_thread_amount_is_still_accepted() {
+        let command = parse_arguments(&to_arguments(&["4"])).unwrap();
+        let expected = Arguments {
+            threads: Some(4),
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+    }
+
+    #[test]
+    fn all_options_are_parsed() {
+        let command = parse_arguments(&to_arguments(&[
+            "--data-dir",
+            "data",
+            "--output",
+            "report.json",
+            "--threads",
+            "8",
+            "--top-sites",
+            "3",
+            "--top-tags",
+            "5",
+            "--related-tags",
+            "3",
+            "--filter",
+            "tag:rust and words>10",
+            "--state",
+            "state.json",
+            "--id",
+            "12345",
+            "--strict",
+            "--diagnostics",
+        ]))
+        .unwrap();
+        let expected = Arguments {
+            data_dirs: vec!["data".to_string()],
+            output: Some("report.json".to_string()),
+            threads: Some(8),
+            top_sites: 3,
+            top_tags: 5,
+            related_tags: 3,
+            filter: Some("tag:rust and words>10".to_string()),
+            state: Some("state.json".to_string()),
+            id: "12345".to_string(),
+            strict: true,
+            diagnostics: true,
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+    }
+
+    #[test]
+    fn text_layouts_are_parsed() {
+        let command = parse_arguments(&to_arguments(&["--text-layout", "body,other"])).unwrap();
+        let expected = Arguments {
+            text_layout: TextLayout::from_spec("body,other").unwrap(),
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--text-layout", "title,answers"])),
+            Err(ErrorExecution::InvalidArgumentValue(_, _))
+        ));
+    }
+
+    #[test]
+    fn schemas_are_parsed() {
+        let command = parse_arguments(&to_arguments(&["--schema", "posts"])).unwrap();
+        let expected = Arguments {
+            schema: InputSchema::Posts,
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--schema", "xml"])),
+            Err(ErrorExecution::InvalidArgumentValue(_, _))
+        ));
+    }
+
+    #[test]
+    fn tokenizers_are_parsed() {
+        let command = parse_arguments(&to_arguments(&["--tokenizer", "prose"])).unwrap();
+        let expected = Arguments {
+            tokenizer: TokenizerKind::Prose,
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--tokenizer", "words"])),
+            Err(ErrorExecution::InvalidArgumentValue(_, _))
+        ));
+    }
+
+    #[test]
+    fn the_last_grouping_rule_is_used() {
+        let command = parse_arguments(&to_arguments(&[
+            "--site-mapping",
+            "sites.json",
+            "--group-pattern",
+            "(.*)\\.part",
+        ]))
+        .unwrap();
+        let expected = Arguments {
+            grouping: Some(GroupingOption::Pattern("(.*)\\.part".to_string())),
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+    }
+
+    #[test]
+    fn duplicate_policies_are_parsed() {
+        let command = parse_arguments(&to_arguments(&["--duplicates", "error"])).unwrap();
+        let expected = Arguments {
+            duplicates: DuplicateSites::Error,
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--duplicates", "keep"])),
+            Err(ErrorExecution::InvalidArgumentValue(_, _))
+        ));
+    }
+
+    #[test]
+    fn output_formats_are_parsed() {
+        for (value, format) in [
+            ("json", OutputFormat::Json),
+            ("csv", OutputFormat::Table(TableFormat::Csv)),
+            ("tsv", OutputFormat::Table(TableFormat::Tsv)),
+        ] {
+            let command = parse_arguments(&to_arguments(&["--format", value])).unwrap();
+            let expected = Arguments {
+                format,
+                ..Arguments::default()
+            };
+            assert_eq!(command, Command::Run(Box::new(expected)));
+        }
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--format", "xml"])),
+            Err(ErrorExecution::InvalidArgumentValue(_, _))
+        ));
+    }
+
+    #[test]
+    fn chatty_tags_options_are_parsed() {
+        let command = parse_arguments(&to_arguments(&[
+            "--min-tag-questions",
+            "5",
+            "--tag-prior-weight",
+            "10",
+        ]))
+        .unwrap();
+        let expected = Arguments {
+            min_tag_questions: 5,
+            tag_prior_weight: 10.0,
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+
+        for weight in ["-1", "NaN", "inf", "heavy"] {
+            assert!(matches!(
+                parse_arguments(&to_arguments(&["--tag-prior-weight", weight])),
+                Err(ErrorExecution::InvalidArgumentValue(_, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn rankings_are_parsed() {
+        let command = parse_arguments(&to_arguments(&[
+            "--rank",
+            "popular=questions",
+            "--rank",
+            "least_verbose=median_words:asc:min=5:top=3",
+        ]))
+        .unwrap();
+        let expected = Arguments {
+            rankings: vec![
+                (
+                    "popular".to_string(),
+                    Ranking::new(RankingMetric::Questions),
+                ),
+                (
+                    "least_verbose".to_string(),
+                    Ranking::new(RankingMetric::MedianWords)
+                        .order(RankingOrder::Ascending)
+                        .min_questions(5)
+                        .limit(3),
+                ),
+            ],
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--rank", "smooth=ratio:prior=2.5"])),
+            Ok(Command::Run(arguments))
+                if arguments.rankings[0].1 == Ranking::new(RankingMetric::Ratio).prior_weight(2.5)
+        ));
+
+        for spec in [
+            "popular",
+            "=ratio",
+            "popular=length",
+            "popular=ratio:min=x",
+            "popular=ratio:up",
+        ] {
+            assert!(matches!(
+                parse_arguments(&to_arguments(&["--rank", spec])),
+                Err(ErrorExecution::InvalidArgumentValue(_, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn input_options_can_be_repeated() {
+        let command = parse_arguments(&to_arguments(&[
+            "-d",
+            "first",
+            "--data-dir",
+            "second",
+            "--file",
+            "site.jsonl",
+            "-r",
+            "--include",
+            "*.stackexchange.com*",
+            "--exclude",
+            "meta.*",
+        ]))
+        .unwrap();
+        let expected = Arguments {
+            data_dirs: vec!["first".to_string(), "second".to_string()],
+            files: vec!["site.jsonl".to_string()],
+            recursive: true,
+            include: vec!["*.stackexchange.com*".to_string()],
+            exclude: vec!["meta.*".to_string()],
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+    }
+
+    #[test]
+    fn only_files_do_not_use_the_default_directory() {
+        let command = parse_arguments(&to_arguments(&["-f", "site.jsonl"])).unwrap();
+        let expected = Arguments {
+            data_dirs: Vec::new(),
+            files: vec!["site.jsonl".to_string()],
+            ..Arguments::default()
+        };
+        assert_eq!(command, Command::Run(Box::new(expected)));
+    }
+
+    #[test]
+    fn help_and_version() {
+        assert_eq!(
+            parse_arguments(&to_arguments(&["-h"])).unwrap(),
+            Command::Help
+        );
+        assert_eq!(
+            parse_arguments(&to_arguments(&["--version"])).unwrap(),
+            Command::Version
+        );
+    }
+
+    #[test]
+    fn invalid_arguments_are_errors() {
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--threads", "zero"])),
+            Err(ErrorExecution::InvalidNumberOfThreads)
+        ));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--output"])),
+            Err(ErrorExecution::MissingArgumentValue(_))
+        ));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--top-tags", "-1"])),
+            Err(ErrorExecution::InvalidArgumentValue(_, _))
+        ));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--verbose"])),
+            Err(ErrorExecution::UnknownArgument(_))
+        ));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["4", "8"])),
+            Err(ErrorExecution::WrongAmountOfParameters)
+        ));
+    }
+
+    #[test]
+    fn threads_are_given_once_in_any_order() {
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["4", "--threads", "8"])),
+            Err(ErrorExecution::WrongAmountOfParameters)
+        ));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["--threads", "8", "4"])),
+            Err(ErrorExecution::WrongAmountOfParameters)
+        ));
+        assert!(matches!(
+            parse_arguments(&to_arguments(&["-t", "8", "4"])),
+            Err(ErrorExecution::WrongAmountOfParameters)
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+use std::{env::args, path::PathBuf, str::FromStr};
+
+use tp1_fork_join_108225::corpus_generator::{generate_corpus, CorpusConfig, LengthDistribution};
+
+const USAGE: &str = "Usage: generate_corpus --output <DIR> [OPTIONS]
+
+Writes a deterministic synthetic corpus, one .jsonl file per site.
+
+Options:
+  -o, --output <DIR>           Directory where the files are written
+      --seed <N>               Seed of the generator (default: 108225)
+      --sites <N>              Amount of sites (default: 4)
+      --questions <N>          Amount of lines of each site (default: 1000)
+      --tags <N>               Amount of different tags (default: 200)
+      --zipf <S>               Exponent of the Zipf distribution of the tags (default: 1.0)
+      --min-tags <N>           Minimum amount of tags of a question (default: 1)
+      --max-tags <N>           Maximum amount of tags of a question (default: 5)
+      --title-words <MIN:MAX>  Uniform range of the words of the titles (default: 4:15)
+      --body-words <MEAN>      Mean of the exponential distribution of the words of the bodies (default: 150)
+      --malformed <RATIO>      Probability of a line being malformed (default: 0)
+  -h, --help                   Prints this message";
+
+/// Parses the value of an option. If there is no value or it is invalid, it returns an error message.
+///
+/// # Arguments
+///
+/// * `option` - The option that expects the value.
+/// * `value` - The argument that follows the option, if any.
+///
+/// # Returns
+///
+/// A result with the parsed value or an error message.
+fn parse_value<T: FromStr>(option: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or(format!("The option '{}' requires a value", option))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for the option '{}'", value, option))
+}
+
+/// Parses a uniform range written as `MIN:MAX`. If it is invalid, it returns an error message.
+///
+/// # Arguments
+///
+/// * `option` - The option that expects the range.
+/// * `value` - The argument that follows the option, if any.
+///
+/// # Returns
+///
+/// A result with the distribution or an error message.
+fn parse_range(option: &str, value: Option<&String>) -> Result<LengthDistribution, String> {
+    let range: String = parse_value(option, value)?;
+    let invalid = || format!("Invalid value '{}' for the option '{}'", range, option);
+    let (min, max) = range.split_once(':').ok_or_else(invalid)?;
+    let min = min.parse().map_err(|_| invalid())?;
+    let max = max.parse().map_err(|_| invalid())?;
+    Ok(LengthDistribution::Uniform(min, max))
+}
+
+/// Parses the arguments of the program into the configuration and the output directory.
+/// If help is requested, it returns None.
+///
+/// # Arguments
+///
+/// * `arguments` - The arguments provided to the program.
+///
+/// # Returns
+///
+/// A result with the configuration and the output directory, or an error message.
+fn parse_arguments(arguments: &[String]) -> Result<Option<(CorpusConfig, PathBuf)>, String> {
+    let mut config = CorpusConfig::default();
+    let mut output = None;
+    let mut remaining = arguments.iter().skip(1);
+
+    while let Some(argument) = remaining.next() {
+        let option = argument.as_str();
+        match option {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => output = Some(parse_value::<PathBuf>(option, remaining.next())?),
+            "--seed" => config.seed = parse_value(option, remaining.next())?,
+            "--sites" => config.sites = parse_value(option, remaining.next())?,
+            "--questions" => config.questions_per_site = parse_value(option, remaining.next())?,
+            "--tags" => config.tags = parse_value(option, remaining.next())?,
+            "--zipf" => config.tag_exponent = parse_value(option, remaining.next())?,
+            "--min-tags" => config.min_tags = parse_value(option, remaining.next())?,
+            "--max-tags" => config.max_tags = parse_value(option, remaining.next())?,
+            "--title-words" => config.title_words = parse_range(option, remaining.next())?,
+            "--body-words" => {
+                config.body_words =
+                    LengthDistribution::Exponential(parse_value(option, remaining.next())?)
+            }
+            "--malformed" => config.malformed_ratio = parse_value(option, remaining.next())?,
+            other => return Err(format!("Unknown argument '{}'", other)),
+        }
+    }
+
+    config.validate()?;
+    match output {
+        Some(output) => Ok(Some((config, output))),
+        None => Err("The option '--output' is required".to_string()),
+    }
+}
+
+/// Generates the corpus described by the arguments and prints its summary.
+fn main() {
+    let arguments: Vec<String> = args().collect();
+    let (config, output) = match parse_arguments(&arguments) {
+        Ok(Some(parsed)) => parsed,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    match generate_corpus(&config, &output) {
+        Ok(summary) => println!(
+            "Wrote {} files with {} questions, {} words and {} malformed lines ({} bytes) in {}",
+            summary.files,
+            summary.questions,
+            summary.words,
+            summary.malformed_lines,
+            summary.bytes,
+            output.display()
+        ),
+        Err(error) => {
+            eprintln!("Error while writing the corpus: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    fs::{create_dir_all, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use rayon::prelude::*;
+use serde_json::json;
+
+/// Enum that represents the distribution of the amount of words of a text.
+///
+/// # Variants
+///
+/// * `Fixed` - Every text has the same amount of words.
+/// * `Uniform` - The amount of words is uniformly distributed between the two values, inclusive.
+/// * `Exponential` - The amount of words follows an exponential distribution with the given mean,
+///   producing a few very long texts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LengthDistribution {
+    Fixed(u64),
+    Uniform(u64, u64),
+    Exponential(f64),
+}
+
+impl LengthDistribution {
+    /// Samples an amount of words from the distribution.
+    fn sample(&self, random: &mut SplitMix64) -> u64 {
+        match *self {
+            LengthDistribution::Fixed(words) => words,
+            LengthDistribution::Uniform(min, max) => random.next_between(min, max),
+            LengthDistribution::Exponential(mean) => {
+                (-(1.0 - random.next_f64()).ln() * mean.max(0.0)).round() as u64
+            }
+        }
+    }
+}
+
+/// Configuration of a synthetic corpus.
+///
+/// # Attributes
+///
+/// * `seed` - The seed of the pseudo random generator.
+/// * `sites` - The amount of sites, each of them written in its own file.
+/// * `questions_per_site` - The amount of lines of each file, including the malformed ones.
+/// * `tags` - The amount of different tags.
+/// * `tag_exponent` - The exponent of the Zipf distribution of the tags. 0 makes every tag equally likely.
+/// * `min_tags` - The minimum amount of tags of a question.
+/// * `max_tags` - The maximum amount of tags of a question.
+/// * `words` - The amount of different words of the texts.
+/// * `title_words` - The distribution of the amount of words of the titles.
+/// * `body_words` - The distribution of the amount of words of the bodies.
+/// * `malformed_ratio` - The probability of a line being malformed, between 0 and 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusConfig {
+    pub seed: u64,
+    pub sites: u32,
+    pub questions_per_site: u64,
+    pub tags: u64,
+    pub tag_exponent: f64,
+    pub min_tags: u8,
+    pub max_tags: u8,
+    pub words: u64,
+    pub title_words: LengthDistribution,
+    pub body_words: LengthDistribution,
+    pub malformed_ratio: f64,
+}
+
+impl CorpusConfig {
+    /// Checks that the ranges of the configuration are not empty.
+    ///
+    /// # Returns
+    ///
+    /// A result with nothing or a message describing the invalid range.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_tags > self.max_tags {
+            return Err(format!(
+                "The minimum amount of tags ({}) is greater than the maximum ({})",
+                self.min_tags, self.max_tags
+            ));
+        }
+        for (name, distribution) in [("title", self.title_words), ("body", self.body_words)] {
+            if let LengthDistribution::Uniform(min, max) = distribution {
+                if min > max {
+                    return Err(format!(
+                        "The minimum amount of words of the {} texts ({}) is greater than the maximum ({})",
+                        name, min, max
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for CorpusConfig {
+    /// Creates a small corpus configuration, similar in shape to the real data.
+    ///
+    /// # Returns
+    ///
+    /// A new CorpusConfig instance.
+    fn default() -> Self {
+        CorpusConfig {
+            seed: 108225,
+            sites: 4,
+            questions_per_site: 1000,
+            tags: 200,
+            tag_exponent: 1.0,
+            min_tags: 1,
+            max_tags: 5,
+            words: 5000,
+            title_words: LengthDistribution::Uniform(4, 15),
+            body_words: LengthDistribution::Exponential(150.0),
+            malformed_ratio: 0.0,
+        }
+    }
+}
+
+/// Summary of what was written in a corpus, used to check the results of the analysis.
+///
+/// # Attributes
+///
+/// * `files` - The amount of files written.
+/// * `questions` - The amount of valid questions written.
+/// * `words` - The amount of words of the valid questions.
+/// * `malformed_lines` - The amount of malformed lines written.
+/// * `bytes` - The size of the corpus in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CorpusSummary {
+    pub files: u64,
+    pub questions: u64,
+    pub words: u64,
+    pub malformed_lines: u64,
+    pub bytes: u64,
+}
+
+impl CorpusSummary {
+    /// Merges the summary with the summary of another part of the corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other summary to merge with
+    pub fn merge(&mut self, other: Self) {
+        self.files += other.files;
+        self.questions += other.questions;
+        self.words += other.words;
+        self.malformed_lines += other.malformed_lines;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Pseudo random generator with a fixed algorithm, so the corpora do not change between versions.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number in [0, bound). The bound must be greater than 0.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Returns a number in [min, max]. The minimum must not be greater than the maximum.
+    fn next_between(&mut self, min: u64, max: u64) -> u64 {
+        match (max - min).checked_add(1) {
+            Some(span) => min + self.next_below(span),
+            // The range holds every u64.
+            None => self.next_u64(),
+        }
+    }
+}
+
+/// Cumulative weights of a Zipf distribution, sampled with a binary search.
+struct Zipf {
+    cumulative: Vec<f64>,
+}
+
+impl Zipf {
+    fn new(elements: u64, exponent: f64) -> Self {
+        let mut total = 0.0;
+        let cumulative = (1..=elements.max(1))
+            .map(|rank| {
+                total += 1.0 / (rank as f64).powf(exponent);
+                total
+            })
+            .collect();
+        Zipf { cumulative }
+    }
+
+    /// Returns the index of the sampled element, where 0 is the most likely one.
+    fn sample(&self, random: &mut SplitMix64) -> u64 {
+        let total = self.cumulative[self.cumulative.len() - 1];
+        let target = random.next_f64() * total;
+        self.cumulative
+            .partition_point(|weight| *weight <= target)
+            .min(self.cumulative.len() - 1) as u64
+    }
+}
+
+/// Returns the name of a site, as it appears in the reports.
+///
+/// # Arguments
+///
+/// * `site` - The index of the site.
+///
+/// # Returns
+///
+/// The name of the site.
+pub fn site_name(site: u32) -> String {
+    format!("site{}.stackexchange.com", site)
+}
+
+/// Returns the name of the file of a site.
+///
+/// # Arguments
+///
+/// * `site` - The index of the site.
+///
+/// # Returns
+///
+/// The name of the file.
+pub fn site_file_name(site: u32) -> String {
+    format!("{}.jsonl", site_name(site))
+}
+
+/// Checks the configuration of the corpus, as an error of invalid input.
+fn validate_config(config: &CorpusConfig) -> std::io::Result<()> {
+    config
+        .validate()
+        .map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidInput, message))
+}
+
+/// Writes the lines of one site of the corpus.
+/// Each site uses its own sequence of random numbers, so the sites can be written in any order.
+/// A configuration with an empty range is rejected before anything is written.
+///
+/// # Arguments
+///
+/// * `config` - The configuration of the corpus.
+/// * `site` - The index of the site.
+/// * `writer` - Where the lines are written.
+///
+/// # Returns
+///
+/// A result with the summary of the site, or the error of the writer or of the configuration.
+pub fn generate_site<W: Write>(
+    config: &CorpusConfig,
+    site: u32,
+    writer: &mut W,
+) -> std::io::Result<CorpusSummary> {
+    validate_config(config)?;
+    let mut random = SplitMix64(config.seed ^ (site as u64).wrapping_mul(0xA24B_AED4_963E_E407));
+    let zipf = Zipf::new(config.tags, config.tag_exponent);
+    let words = config.words.max(1);
+    let mut summary = CorpusSummary {
+        files: 1,
+        ..CorpusSummary::default()
+    };
+
+    for _ in 0..config.questions_per_site {
+        let line = if random.next_f64() < config.malformed_ratio {
+            summary.malformed_lines += 1;
+            malformed_line(&mut random)
+        } else {
+            let title_words = config.title_words.sample(&mut random);
+            let body_words = config.body_words.sample(&mut random);
+            let title = text(&mut random, title_words, words);
+            let body = text(&mut random, body_words, words);
+            let tag_amount = random.next_between(config.min_tags as u64, config.max_tags as u64);
+            let mut tags: Vec<String> = Vec::new();
+            for _ in 0..tag_amount {
+                let tag = format!("tag{}", zipf.sample(&mut random));
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            summary.questions += 1;
+            summary.words += title_words + body_words;
+            json!({ "texts": [title, body], "tags": tags }).to_string()
+        };
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")?;
+        summary.bytes += line.len() as u64 + 1;
+    }
+    Ok(summary)
+}
+
+/// Writes the corpus in the provided directory, one file per site, generating the sites in parallel.
+/// If the directory does not exist, it is created.
+///
+/// # Arguments
+///
+/// * `config` - The configuration of the corpus.
+/// * `directory` - The directory where the files are written.
+///
+/// # Returns
+///
+/// A result with the summary of the corpus or the first error found while writing it.
+/// If the configuration is invalid, nothing is written and an `InvalidInput` error is returned.
+pub fn generate_corpus(config: &CorpusConfig, directory: &Path) -> std::io::Result<CorpusSummary> {
+    validate_config(config)?;
+    create_dir_all(directory)?;
+    (0..config.sites)
+        .into_par_iter()
+        .map(|site| {
+            let file = File::create(directory.join(site_file_name(site)))?;
+            let mut writer = BufWriter::new(file);
+            let summary = generate_site(config, site, &mut writer)?;
+            writer.flush()?;
+            Ok(summary)
+        })
+        .try_reduce(CorpusSummary::default, |mut summary, other| {
+            summary.merge(other);
+            Ok(summary)
+        })
+}
+
+/// Returns a text with the provided amount of words taken from the vocabulary.
+fn text(random: &mut SplitMix64, amount: u64, words: u64) -> String {
+    let mut text = String::new();
+    for index in 0..amount {
+        if index > 0 {
+            text.push(' ');
+        }
+        text.push_str("word");
+        text.push_str(&random.next_below(words).to_string());
+    }
+    text
+}
+
+/// Returns a line that can not be parsed as a question.
+fn malformed_line(random: &mut SplitMix64) -> String {
+    match random.next_below(3) {
+        0 => "{\"texts\": [\"truncated question".to_string(),
+        1 => "{\"texts\": [\"question without tags\"]}".to_string(),
+        _ => "this line is not json".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_in_memory(config: &CorpusConfig, site: u32) -> (Vec<u8>, CorpusSummary) {
+        let mut buffer = Vec::new();
+        let summary = generate_site(config, site, &mut buffer).unwrap();
+        (buffer, summary)
+    }
+
+    #[test]
+    fn same_seed_generates_same_corpus() {
+        let config = CorpusConfig::default();
+        assert_eq!(
+            generate_in_memory(&config, 0),
+            generate_in_memory(&config, 0)
+        );
+
+        let other_seed = CorpusConfig {
+            seed: 1,
+            ..CorpusConfig::default()
+        };
+        assert_ne!(
+            generate_in_memory(&config, 0).0,
+            generate_in_memory(&other_seed, 0).0
+        );
+        assert_ne!(
+            generate_in_memory(&config, 0).0,
+            generate_in_memory(&config, 1).0
+        );
+    }
+
+    #[test]
+    fn summary_matches_the_lines() {
+        let config = CorpusConfig {
+            questions_per_site: 500,
+            malformed_ratio: 0.1,
+            ..CorpusConfig::default()
+        };
+        let (buffer, summary) = generate_in_memory(&config, 3);
+        let text = String::from_utf8(buffer.clone()).unwrap();
+
+        let mut questions = 0;
+        let mut words = 0;
+        for line in text.lines() {
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
+                if let Some(tags) = value["tags"].as_array() {
+                    questions += 1;
+                    assert!(!tags.is_empty() && tags.len() <= 5);
+                    for text in value["texts"].as_array().unwrap() {
+                        words += text.as_str().unwrap().split_whitespace().count() as u64;
+                    }
+                }
+            }
+        }
+        assert_eq!(questions, summary.questions);
+        assert_eq!(words, summary.words);
+        assert_eq!(summary.questions + summary.malformed_lines, 500);
+        assert!(summary.malformed_lines > 20 && summary.malformed_lines < 80);
+        assert_eq!(summary.bytes, buffer.len() as u64);
+    }
+
+    #[test]
+    fn tags_follow_a_zipf_distribution() {
+        let zipf = Zipf::new(100, 1.0);
+        let mut random = SplitMix64(7);
+        let mut counts = vec![0u64; 100];
+        for _ in 0..20_000 {
+            counts[zipf.sample(&mut random) as usize] += 1;
+        }
+        assert!(counts[0] > counts[1] && counts[1] > counts[9] && counts[9] > counts[99]);
+    }
+
+    #[test]
+    fn lengths_follow_their_distribution() {
+        let mut random = SplitMix64(7);
+        assert_eq!(LengthDistribution::Fixed(12).sample(&mut random), 12);
+        for _ in 0..1000 {
+            let words = LengthDistribution::Uniform(3, 6).sample(&mut random);
+            assert!((3..=6).contains(&words));
+        }
+        assert_eq!(LengthDistribution::Uniform(9, 9).sample(&mut random), 9);
+        LengthDistribution::Uniform(0, u64::MAX).sample(&mut random);
+        LengthDistribution::Uniform(1, u64::MAX).sample(&mut random);
+        let total: u64 = (0..10_000)
+            .map(|_| LengthDistribution::Exponential(100.0).sample(&mut random))
+            .sum();
+        let mean = total as f64 / 10_000.0;
+        assert!(mean > 90.0 && mean < 110.0);
+    }
+
+    #[test]
+    fn empty_ranges_are_rejected() {
+        assert!(CorpusConfig::default().validate().is_ok());
+        let reversed_words = CorpusConfig {
+            title_words: LengthDistribution::Uniform(15, 4),
+            ..CorpusConfig::default()
+        };
+        assert!(reversed_words.validate().is_err());
+        let reversed_tags = CorpusConfig {
+            min_tags: 5,
+            max_tags: 1,
+            ..CorpusConfig::default()
+        };
+        assert!(reversed_tags.validate().is_err());
+        let directory =
+            std::env::temp_dir().join(format!("tp1_empty_ranges_{}", std::process::id()));
+        let error = generate_corpus(&reversed_tags, &directory).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        let mut lines = Vec::new();
+        let error = generate_site(&reversed_words, 0, &mut lines).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(lines.is_empty());
+    }
+}
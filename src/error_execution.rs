@@ -1,3 +1,5 @@
+use std::fmt;
+
 use tp1_fork_join_108225::sites_information::parsing_error::ParsingError;
 
 /// Enum that represents the possible errors that can occur during the execution of the program.
@@ -6,12 +8,53 @@ use tp1_fork_join_108225::sites_information::parsing_error::ParsingError;
 ///
 /// * `WrongAmountOfParameters` - The amount of parameters provided to the program is invalid.
 /// * `InvalidNumberOfThreads` - The number of threads provided to the program is invalid.
+/// * `UnknownArgument` - An option provided to the program is not recognized.
+/// * `MissingArgumentValue` - An option provided to the program is missing its value.
+/// * `InvalidArgumentValue` - The value of an option provided to the program is invalid.
 /// * `ErrorInSites` - An error occurred while loading the sites.
 /// * `ErrorInJsonParsing` - An error occurred while parsing the JSON.
+/// * `ErrorWritingOutput` - An error occurred while writing the report.
 #[derive(Debug)]
 pub enum ErrorExecution {
     WrongAmountOfParameters,
     InvalidNumberOfThreads,
+    UnknownArgument(String),
+    MissingArgumentValue(String),
+    InvalidArgumentValue(String, String),
     ErrorInSites(ParsingError),
     ErrorInJsonParsing,
+    ErrorWritingOutput(String),
+}
+
+impl fmt::Display for ErrorExecution {
+    /// Formats the error as a message that can be shown to the user.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorExecution::WrongAmountOfParameters => {
+                write!(f, "Wrong number of arguments provided")
+            }
+            ErrorExecution::InvalidNumberOfThreads => {
+                write!(
+                    f,
+                    "The number of threads must be a number between 0 and 255"
+                )
+            }
+            ErrorExecution::UnknownArgument(argument) => {
+                write!(f, "Unknown argument '{}'", argument)
+            }
+            ErrorExecution::MissingArgumentValue(option) => {
+                write!(f, "The option '{}' requires a value", option)
+            }
+            ErrorExecution::InvalidArgumentValue(option, value) => {
+                write!(f, "Invalid value '{}' for the option '{}'", value, option)
+            }
+            ErrorExecution::ErrorInSites(error) => {
+                write!(f, "Error while loading the sites: {}", error)
+            }
+            ErrorExecution::ErrorInJsonParsing => write!(f, "Error while generating the JSON"),
+            ErrorExecution::ErrorWritingOutput(error) => {
+                write!(f, "Error while writing the report: {}", error)
+            }
+        }
+    }
 }
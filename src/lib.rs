@@ -1,2 +1,5 @@
 /// This module contains the main logic of the application.
 pub mod sites_information;
+
+/// Generates deterministic synthetic corpora with the format of the data, for tests and benchmarks.
+pub mod corpus_generator;
@@ -1,75 +1,306 @@
+mod arguments;
 mod error_execution;
 
+use arguments::{parse_arguments, usage, Arguments, Command, GroupingOption, OutputFormat};
 use error_execution::ErrorExecution;
-use serde_json::Value;
-use std::env::args;
-use tp1_fork_join_108225::sites_information::sites_collection::SitesCollection;
+use std::{
+    env::args,
+    fs::{create_dir_all, write, File},
+    io::{stdout, BufWriter, Write},
+    path::Path,
+    process::ExitCode,
+};
+use tp1_fork_join_108225::sites_information::{
+    analysis_state::AnalysisState,
+    diagnostics::Diagnostics,
+    export::{report_tables, TableFormat},
+    input_selection::InputSelection,
+    question_filter::QuestionFilter,
+    ranking::Ranking,
+    report::Report,
+    site_grouping::SiteGrouping,
+    sites_collection::SitesCollection,
+};
 
-const DATA_PATH: &str = "test_data";
-const PADRON: &str = "108225";
-const ARG_AMOUNT: usize = 2;
+/// Exit code of the runs with invalid arguments.
+const USAGE_ERROR: u8 = 2;
 
-/// Verifies the amount of arguments provided to the program. If the amount is invalid, it returns an error.
+/// Writes the report in the output file if there is one, or prints it in stdout otherwise.
 ///
 /// # Arguments
 ///
-/// * `arguments` - A vector of strings containing the arguments provided to the program.
+/// * `report` - The report to write.
+/// * `output` - The path of the output file, if any.
 ///
 /// # Returns
 ///
 /// A result that could carry an ErrorExecution.
-fn verify_amount_of_arguments(arguments: &[String]) -> Result<(), ErrorExecution> {
-    if arguments.len() != ARG_AMOUNT {
-        eprintln!("Wonrg number of arguments provided. The program should be executed with cargo run <number_of_working_threads>");
-        return Err(ErrorExecution::WrongAmountOfParameters);
+fn write_report(report: &str, output: &Option<String>) -> Result<(), ErrorExecution> {
+    match output {
+        Some(path) => match write(path, report) {
+            Ok(()) => Ok(()),
+            Err(error) => Err(ErrorExecution::ErrorWritingOutput(error.to_string())),
+        },
+        None => {
+            println!("{}", report);
+            Ok(())
+        }
     }
-    Ok(())
 }
 
-/// Returns the number of threads to be used by the program.
-/// If the number is invalid, it returns an error.
+/// Writes the tables of the report in the output directory if there is one, one file per table,
+/// or prints them in stdout separated by an empty line otherwise.
 ///
 /// # Arguments
 ///
-/// * `number_of_threads` - A string containing the number of threads to be used by the program.
+/// * `report` - The report whose tables are written.
+/// * `format` - The format of the tables.
+/// * `output` - The path of the output directory, if any.
 ///
 /// # Returns
 ///
-/// A result with the number of threads to be used by the program.
-fn obtain_number_worker_threads(number_of_threads: &str) -> Result<u8, ErrorExecution> {
-    match number_of_threads.parse::<u8>() {
-        Ok(number) => Ok(number),
-        Err(_) => Err(ErrorExecution::InvalidNumberOfThreads),
+/// A result that could carry an ErrorExecution.
+fn write_tables(
+    report: &Report,
+    format: TableFormat,
+    output: &Option<String>,
+) -> Result<(), ErrorExecution> {
+    let tables = report_tables(report);
+    let result = match output {
+        Some(directory) => create_dir_all(directory).and_then(|_| {
+            tables.iter().try_for_each(|table| {
+                let path = Path::new(directory).join(table.file_name(format));
+                let mut writer = BufWriter::new(File::create(path)?);
+                table.write(&mut writer, format)?;
+                writer.flush()
+            })
+        }),
+        None => {
+            let mut writer = stdout().lock();
+            tables.iter().enumerate().try_for_each(|(index, table)| {
+                if index > 0 {
+                    writeln!(writer)?;
+                }
+                table.write(&mut writer, format)
+            })
+        }
+    };
+    result.map_err(|error| ErrorExecution::ErrorWritingOutput(error.to_string()))
+}
+
+/// Builds the selection of the files to analyse from the arguments.
+/// If a pattern is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `arguments` - The configuration obtained from the command line.
+///
+/// # Returns
+///
+/// A result with the selection or an ErrorExecution.
+fn build_selection(arguments: &Arguments) -> Result<InputSelection, ErrorExecution> {
+    let mut selection = InputSelection::new().recursive(arguments.recursive);
+    for directory in &arguments.data_dirs {
+        selection = selection.directory(directory);
+    }
+    for file in &arguments.files {
+        selection = selection.file(file);
+    }
+    for pattern in &arguments.include {
+        selection = selection
+            .include(pattern)
+            .map_err(ErrorExecution::ErrorInSites)?;
+    }
+    for pattern in &arguments.exclude {
+        selection = selection
+            .exclude(pattern)
+            .map_err(ErrorExecution::ErrorInSites)?;
     }
+    Ok(selection)
 }
 
-/// Main function of the program.
-/// It receives the number of threads to be used by the program as an argument in the command line.
-/// It loads the sites from the file "test_data" and generates a JSON report with the information of the sites.
-/// It prints the report in stdout.
-fn main() -> Result<(), ErrorExecution> {
-    let arguments: Vec<String> = args().collect();
-    verify_amount_of_arguments(&arguments)?;
-    let num_threads = obtain_number_worker_threads(&arguments[1])?;
+/// Builds the rule that assigns the files to their sites from the arguments.
+/// If the pattern is invalid or the mapping file can not be read, it returns an error.
+///
+/// # Arguments
+///
+/// * `grouping` - The rule requested in the command line, if any.
+///
+/// # Returns
+///
+/// A result with the grouping rule or an ErrorExecution.
+fn build_grouping(grouping: &Option<GroupingOption>) -> Result<SiteGrouping, ErrorExecution> {
+    let grouping = match grouping {
+        Some(GroupingOption::Pattern(pattern)) => SiteGrouping::pattern(pattern),
+        Some(GroupingOption::Mapping(path)) => SiteGrouping::from_mapping_file(Path::new(path)),
+        None => Ok(SiteGrouping::ByName),
+    };
+    grouping.map_err(ErrorExecution::ErrorInSites)
+}
 
-    let mut sites = SitesCollection::new();
+/// Builds the filter of the questions from the arguments.
+/// If the expression is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `filter` - The expression requested in the command line, if any.
+///
+/// # Returns
+///
+/// A result with the filter, if one was requested, or an ErrorExecution.
+fn build_filter(filter: &Option<String>) -> Result<Option<QuestionFilter>, ErrorExecution> {
+    match filter {
+        Some(expression) => QuestionFilter::parse(expression)
+            .map(Some)
+            .map_err(ErrorExecution::ErrorInSites),
+        None => Ok(None),
+    }
+}
 
-    if let Err(er) = sites.set_thread_amount(num_threads) {
-        return Err(ErrorExecution::ErrorInSites(er));
+/// Loads the sites reusing the state stored in the state file, if it exists, and writes the
+/// updated state to it.
+///
+/// # Arguments
+///
+/// * `sites` - The collection where the sites are loaded.
+/// * `selection` - The selection of the files to analyse.
+/// * `state_path` - The path of the state file.
+///
+/// # Returns
+///
+/// A result with the diagnostics of the loading or an ErrorExecution.
+fn load_incrementally(
+    sites: &mut SitesCollection,
+    selection: &InputSelection,
+    state_path: &Path,
+) -> Result<Diagnostics, ErrorExecution> {
+    let previous = if state_path.exists() {
+        Some(AnalysisState::load(state_path).map_err(ErrorExecution::ErrorInSites)?)
+    } else {
+        None
+    };
+    let load = sites
+        .load_sites_incrementally(selection, previous.as_ref())
+        .map_err(ErrorExecution::ErrorInSites)?;
+    eprintln!(
+        "Reused {} files, read the appended lines of {} files and read {} files",
+        load.unchanged, load.appended, load.processed
+    );
+    load.state
+        .save(state_path)
+        .map_err(ErrorExecution::ErrorInSites)?;
+    Ok(load.diagnostics)
+}
+
+/// Loads the sites from the data directories and writes the report with their information
+/// in the requested format.
+///
+/// # Arguments
+///
+/// * `arguments` - The configuration obtained from the command line.
+///
+/// # Returns
+///
+/// A result that could carry an ErrorExecution.
+fn run(arguments: Arguments) -> Result<(), ErrorExecution> {
+    let mut builder = SitesCollection::builder()
+        .strict(arguments.strict)
+        .site_grouping(build_grouping(&arguments.grouping)?)
+        .duplicate_sites(arguments.duplicates)
+        .input_schema(arguments.schema)
+        .text_layout(arguments.text_layout.clone())
+        .tokenizer(arguments.tokenizer.tokenizer())
+        .question_filter(build_filter(&arguments.filter)?)
+        .tag_pairs(arguments.related_tags > 0);
+    if let Some(num_threads) = arguments.threads {
+        builder = builder.num_threads(num_threads);
+    }
+
+    let mut sites = match builder.build() {
+        Ok(sites) => sites,
+        Err(er) => return Err(ErrorExecution::ErrorInSites(er)),
     };
 
-    if let Err(er) = sites.load_sites(DATA_PATH) {
-        return Err(ErrorExecution::ErrorInSites(er));
+    let selection = build_selection(&arguments)?;
+
+    let diagnostics = match &arguments.state {
+        Some(state_path) => load_incrementally(&mut sites, &selection, Path::new(state_path))?,
+        None => match sites.load_sites_from(&selection) {
+            Ok(diagnostics) => diagnostics,
+            Err(er) => return Err(ErrorExecution::ErrorInSites(er)),
+        },
     };
 
-    let parsed: Value = sites.generate_json_information(PADRON);
+    for (site, files) in diagnostics.merged_sites() {
+        eprintln!("Merged {} files of the site {}", files.len(), site);
+    }
+
+    if !diagnostics.is_empty() {
+        eprintln!(
+            "Skipped {} lines and {} files while loading the sites",
+            diagnostics.skipped_lines(),
+            diagnostics.skipped_files()
+        );
+    }
+
+    let chatty_tags = Ranking::chatty(arguments.top_tags)
+        .min_questions(arguments.min_tag_questions)
+        .prior_weight(arguments.tag_prior_weight);
+    let mut report = sites.generate_ranked_report(
+        &arguments.id,
+        &Ranking::chatty(arguments.top_sites),
+        &chatty_tags,
+    );
+
+    report.leaderboards = sites.generate_leaderboards(&arguments.rankings);
+
+    if arguments.related_tags > 0 {
+        report.related_tags =
+            Some(sites.generate_related_tags_report(arguments.related_tags as usize));
+    }
+
+    if arguments.diagnostics {
+        report.diagnostics = Some(diagnostics.generate_report());
+    }
+
+    match arguments.format {
+        OutputFormat::Json => {
+            let string_report = match report.to_json() {
+                Ok(report) => report,
+                Err(_) => return Err(ErrorExecution::ErrorInJsonParsing),
+            };
+            write_report(&string_report, &arguments.output)
+        }
+        OutputFormat::Table(format) => write_tables(&report, format, &arguments.output),
+    }
+}
 
-    let string_report = match serde_json::to_string_pretty(&parsed) {
-        Ok(report) => report,
-        Err(_) => return Err(ErrorExecution::ErrorInJsonParsing),
+/// Main function of the program.
+/// It receives the options of the run as arguments in the command line.
+/// It loads the sites from the data directory and generates a JSON report with the information of the sites.
+/// It prints the report in stdout or writes it in the output file.
+/// If the arguments are invalid, it prints the error and the usage and exits with the code 2.
+/// If the run fails, it prints the error and exits with the code 1.
+fn main() -> ExitCode {
+    let arguments: Vec<String> = args().collect();
+    let command = match parse_arguments(&arguments) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, usage());
+            return ExitCode::from(USAGE_ERROR);
+        }
     };
 
-    println!("{}", string_report);
+    match command {
+        Command::Help => println!("{}", usage()),
+        Command::Version => println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        Command::Run(arguments) => {
+            if let Err(error) = run(*arguments) {
+                eprintln!("Error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
 
-    Ok(())
+    ExitCode::SUCCESS
 }
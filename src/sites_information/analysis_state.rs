@@ -0,0 +1,343 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    sync::Arc,
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    diagnostics::{Diagnostics, FileDiagnostics},
+    parsing_error::ParsingError,
+    question_filter::QuestionFilter,
+    question_parser::QuestionParser,
+    site::Site,
+    text_layout::TextLayout,
+};
+
+/// Version of the format of the state files. States with another version are not reused.
+pub const STATE_VERSION: u32 = 5;
+
+/// Offset basis of the 64 bits FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// Prime of the 64 bits FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Represents the contents of a file when it was read, used to detect whether it changed.
+///
+/// # Attributes
+///
+/// * `size` - The size of the file in bytes.
+/// * `modified` - The time of the last modification, in nanoseconds since the Unix epoch, if the
+///   platform provides it.
+/// * `hash` - The FNV-1a hash of the bytes of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub modified: Option<u64>,
+    pub hash: u64,
+}
+
+impl FileFingerprint {
+    /// Takes the fingerprint of a file, reading all its bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// A result with the fingerprint or the error found while reading the file.
+    pub fn of(path: &Path) -> io::Result<Self> {
+        let (size, modified) = file_metadata(path)?;
+        Ok(FileFingerprint {
+            size,
+            modified,
+            hash: hash_file(path, None)?,
+        })
+    }
+}
+
+/// Returns the size and the time of the last modification of a file, without reading it.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+///
+/// # Returns
+///
+/// A result with the size in bytes and the time of the last modification in nanoseconds since
+/// the Unix epoch, if the platform provides it.
+pub fn file_metadata(path: &Path) -> io::Result<(u64, Option<u64>)> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64);
+    Ok((metadata.len(), modified))
+}
+
+/// Returns the FNV-1a hash of the first bytes of a file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+/// * `limit` - The amount of bytes that are hashed. If it is None, the whole file is hashed.
+///
+/// # Returns
+///
+/// A result with the hash or the error found while reading the file.
+pub fn hash_file(path: &Path, limit: Option<u64>) -> io::Result<u64> {
+    let file = BufReader::new(File::open(path)?);
+    let mut reader = file.take(limit.unwrap_or(u64::MAX));
+    let mut buffer = [0u8; 64 * 1024];
+    let mut hash = FNV_OFFSET_BASIS;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hash);
+        }
+        for byte in &buffer[..read] {
+            hash = (hash ^ *byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+/// Returns whether the byte before the provided offset of a file is a line break, so that
+/// the offset is the start of a line.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+/// * `offset` - The offset, greater than zero.
+///
+/// # Returns
+///
+/// A result with whether a line starts at the offset.
+pub fn line_starts_at(path: &Path, offset: u64) -> io::Result<bool> {
+    if offset == 0 {
+        return Ok(false);
+    }
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset - 1))?;
+    let mut byte = [0u8; 1];
+    file.read_exact(&mut byte)?;
+    Ok(byte[0] == b'\n')
+}
+
+/// Represents what was kept of a file after reading it.
+///
+/// # Attributes
+///
+/// * `fingerprint` - The contents of the file when it was read. If it is None, the file could not
+///   be read and it is read again in the next analysis.
+/// * `lines` - The amount of lines of the file.
+/// * `diagnostics` - The problems found in the file.
+/// * `site` - The site stored in the file, if it could be read. It is shared with the sites of the
+///   collection, so it is not copied while the state is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileState {
+    pub fingerprint: Option<FileFingerprint>,
+    pub lines: u64,
+    pub diagnostics: FileDiagnostics,
+    pub site: Option<Arc<Site>>,
+}
+
+/// Enum that represents what was done with a file during an incremental analysis.
+///
+/// # Variants
+///
+/// * `Unchanged` - The file did not change, so its previous state was reused.
+/// * `Appended` - Lines were appended to the file, so only those lines were read.
+/// * `Processed` - The file is new or it changed, so it was read entirely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileChange {
+    Unchanged,
+    Appended,
+    Processed,
+}
+
+/// Represents the persisted state of an analysis: the aggregates of each file and how the
+/// questions were parsed, so that a later analysis only reads the files that changed.
+///
+/// # Attributes
+///
+/// * `version` - The version of the format of the state.
+/// * `schema` - The name of the schema of the questions.
+/// * `layout` - The layout of the texts used to parse the questions.
+/// * `tokenizer` - The name of the tokenizer used to count the words.
+/// * `filter` - The expression of the filter of the questions, if there was one.
+/// * `tag_pairs` - Whether the sites counted the pairs of tags of their questions.
+/// * `files` - The state of each file, by path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisState {
+    pub version: u32,
+    pub schema: String,
+    pub layout: TextLayout,
+    pub tokenizer: String,
+    pub filter: Option<String>,
+    pub tag_pairs: bool,
+    pub files: BTreeMap<String, FileState>,
+}
+
+impl AnalysisState {
+    /// Creates an empty state for the provided parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `parser` - The parser of the questions of the files.
+    ///
+    /// # Returns
+    ///
+    /// A new AnalysisState instance.
+    pub fn new(parser: &QuestionParser) -> Self {
+        AnalysisState {
+            version: STATE_VERSION,
+            schema: parser.schema().name().to_string(),
+            layout: parser.layout().clone(),
+            tokenizer: parser.tokenizer_name().to_string(),
+            filter: parser
+                .filter()
+                .map(|filter| filter.expression().to_string()),
+            tag_pairs: parser.counts_tag_pairs(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Returns whether the files of the state were parsed in the same way as the provided parser
+    /// would parse them, so their aggregates can be reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `parser` - The parser of the questions of the files.
+    ///
+    /// # Returns
+    ///
+    /// True if the state can be reused with the parser.
+    pub fn is_compatible(&self, parser: &QuestionParser) -> bool {
+        self.version == STATE_VERSION
+            && self.schema == parser.schema().name()
+            && &self.layout == parser.layout()
+            && self.tokenizer == parser.tokenizer_name()
+            && self.filter.as_deref() == parser.filter().map(QuestionFilter::expression)
+            && self.tag_pairs == parser.counts_tag_pairs()
+    }
+
+    /// Reads a state from a file. If the file can not be read or it is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the state file.
+    ///
+    /// # Returns
+    ///
+    /// A result with the state or a ParsingError.
+    pub fn load(path: &Path) -> Result<Self, ParsingError> {
+        let invalid =
+            |error: String| ParsingError::ErrorInvalidState(path.display().to_string(), error);
+        let file = File::open(path).map_err(|error| invalid(error.to_string()))?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|error| invalid(error.to_string()))
+    }
+
+    /// Writes the state to a file. The state is written to a temporary file first and then
+    /// renamed, so an interrupted write never leaves a truncated state behind.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the state file.
+    ///
+    /// # Returns
+    ///
+    /// A result that could carry a ParsingError.
+    pub fn save(&self, path: &Path) -> Result<(), ParsingError> {
+        let could_not_write = |error: String| {
+            ParsingError::ErrorCouldNotWriteState(path.display().to_string(), error)
+        };
+        let json = serde_json::to_vec(self).map_err(|error| could_not_write(error.to_string()))?;
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, json)
+            .and_then(|_| fs::rename(&temporary, path))
+            .map_err(|error| could_not_write(error.to_string()))
+    }
+}
+
+/// Represents the result of an incremental loading of a collection of sites.
+///
+/// # Attributes
+///
+/// * `diagnostics` - The problems found in all the files, including the reused ones.
+/// * `state` - The updated state, to be used by the next analysis.
+/// * `unchanged` - The amount of files whose previous state was reused.
+/// * `appended` - The amount of files of which only the appended lines were read.
+/// * `processed` - The amount of files that were read entirely.
+#[derive(Debug)]
+pub struct IncrementalLoad {
+    pub diagnostics: Diagnostics,
+    pub state: AnalysisState,
+    pub unchanged: usize,
+    pub appended: usize,
+    pub processed: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites_information::input_schema::InputSchema;
+
+    const FILE: &str = "tests/testing_data/con_errores/5_preguntas_3_errores.jsonl";
+
+    #[test]
+    fn hashes_depend_on_the_hashed_bytes() {
+        let path = Path::new(FILE);
+        let whole = hash_file(path, None).unwrap();
+        assert_eq!(hash_file(path, None).unwrap(), whole);
+        assert_ne!(hash_file(path, Some(10)).unwrap(), whole);
+        assert_eq!(hash_file(path, Some(u64::MAX)).unwrap(), whole);
+        assert_eq!(FileFingerprint::of(path).unwrap().hash, whole);
+    }
+
+    #[test]
+    fn lines_start_after_line_breaks() {
+        let path = Path::new(FILE);
+        let contents = fs::read(path).unwrap();
+        let second_line = contents.iter().position(|byte| *byte == b'\n').unwrap() as u64 + 1;
+        assert!(line_starts_at(path, second_line).unwrap());
+        assert!(!line_starts_at(path, second_line - 1).unwrap());
+        assert!(!line_starts_at(path, 0).unwrap());
+    }
+
+    #[test]
+    fn states_are_only_compatible_with_the_same_parsing() {
+        let parser = QuestionParser::default();
+        let state = AnalysisState::new(&parser);
+        assert!(state.is_compatible(&parser));
+
+        let mut other = QuestionParser::default();
+        other.set_layout(TextLayout::new(Vec::new()));
+        assert!(!state.is_compatible(&other));
+
+        let mut other = QuestionParser::default();
+        other.set_schema(InputSchema::Posts);
+        assert!(!state.is_compatible(&other));
+
+        let mut other = QuestionParser::default();
+        other.set_filter(Some(QuestionFilter::parse("tag:rust").unwrap()));
+        assert!(!state.is_compatible(&other));
+        assert!(AnalysisState::new(&other).is_compatible(&other));
+
+        let mut other = QuestionParser::default();
+        other.set_tag_pairs(true);
+        assert!(!state.is_compatible(&other));
+
+        let old = AnalysisState {
+            version: STATE_VERSION + 1,
+            ..AnalysisState::new(&parser)
+        };
+        assert!(!old.is_compatible(&parser));
+    }
+}
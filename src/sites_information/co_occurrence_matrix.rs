@@ -0,0 +1,229 @@
+use super::{question_information::unique_tags, report::RelatedTagReport};
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Represents how many questions each pair of tags appeared together in.
+/// The matrix is symmetric: the count of a pair is stored in the row of both tags.
+///
+/// # Attributes
+///
+/// * `rows` - The amount of questions shared with each other tag, by tag.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoOccurrenceMatrix {
+    rows: HashMap<String, HashMap<String, u64>>,
+}
+
+impl CoOccurrenceMatrix {
+    /// Creates an empty co-occurrence matrix.
+    ///
+    /// # Returns
+    ///
+    /// A new CoOccurrenceMatrix instance.
+    pub fn new() -> Self {
+        CoOccurrenceMatrix::default()
+    }
+
+    /// Adds the tags of a question to the matrix, counting every pair of different tags once.
+    /// The counters saturate instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - The tags of the question.
+    pub fn add_tags(&mut self, tags: &[String]) {
+        let tags = unique_tags(tags);
+        for (index, tag) in tags.iter().enumerate() {
+            for other in &tags[index + 1..] {
+                self.add_pair(tag, other, 1);
+                self.add_pair(other, tag, 1);
+            }
+        }
+    }
+
+    /// Adds the provided amount to the count of a pair, only in the row of the first tag.
+    fn add_pair(&mut self, tag: &str, other: &str, amount: u64) {
+        let row = match self.rows.get_mut(tag) {
+            Some(row) => row,
+            None => self.rows.entry(tag.to_string()).or_default(),
+        };
+        match row.get_mut(other) {
+            Some(count) => *count = count.saturating_add(amount),
+            None => {
+                row.insert(other.to_string(), amount);
+            }
+        }
+    }
+
+    /// Merges the matrix with another matrix.
+    /// It takes ownership of the other matrix, making it unusable after the merge.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other matrix to merge with
+    pub fn merge(&mut self, other: Self) {
+        for (tag, other_row) in other.rows {
+            match self.rows.get_mut(&tag) {
+                Some(row) => {
+                    for (other_tag, amount) in other_row {
+                        let count = row.entry(other_tag).or_insert(0);
+                        *count = count.saturating_add(amount);
+                    }
+                }
+                None => {
+                    self.rows.insert(tag, other_row);
+                }
+            }
+        }
+    }
+
+    /// Merges the matrix with another matrix.
+    /// It does not take ownership of the other matrix, so it can be used after the merge.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other matrix to merge with
+    pub fn merge_ref(&mut self, other: &Self) {
+        for (tag, other_row) in &other.rows {
+            for (other_tag, amount) in other_row {
+                self.add_pair(tag, other_tag, *amount);
+            }
+        }
+    }
+
+    /// Returns the amount of questions in which both tags appeared.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - One of the tags.
+    /// * `other` - The other tag.
+    ///
+    /// # Returns
+    ///
+    /// The amount of questions shared by the tags.
+    pub fn count(&self, tag: &str, other: &str) -> u64 {
+        self.rows
+            .get(tag)
+            .and_then(|row| row.get(other))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the amount of pairs of different tags of all the questions, counting each pair
+    /// once per question in which it appeared.
+    ///
+    /// # Returns
+    ///
+    /// The amount of pairs of tags.
+    pub fn pairs(&self) -> u64 {
+        let counts = self
+            .rows
+            .values()
+            .flat_map(|row| row.values())
+            .fold(0u64, |total, count| total.saturating_add(*count));
+        // Every pair is stored in the row of both of its tags.
+        counts / 2
+    }
+
+    /// Returns the tags that appeared together with the provided tag in the most questions.
+    /// Ties are broken by name, so the result does not depend on the order of the questions.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag whose related tags are returned.
+    /// * `amount` - The maximum amount of related tags.
+    ///
+    /// # Returns
+    ///
+    /// The related tags and the amount of questions shared with each one.
+    pub fn related_tags(&self, tag: &str, amount: usize) -> Vec<RelatedTagReport> {
+        let mut related: Vec<(&String, &u64)> = match self.rows.get(tag) {
+            Some(row) => row.iter().collect(),
+            None => return Vec::new(),
+        };
+        related.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        related
+            .into_iter()
+            .take(amount)
+            .map(|(tag, questions)| RelatedTagReport {
+                questions: *questions,
+                tag: tag.clone(),
+            })
+            .collect()
+    }
+
+    /// Generates the related tags of every tag of the matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The maximum amount of related tags of each tag.
+    ///
+    /// # Returns
+    ///
+    /// The related tags of each tag, by name.
+    pub fn generate_report(&self, amount: usize) -> BTreeMap<String, Vec<RelatedTagReport>> {
+        self.rows
+            .keys()
+            .map(|tag| (tag.clone(), self.related_tags(tag, amount)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn names(related: Vec<RelatedTagReport>) -> Vec<(String, u64)> {
+        related
+            .into_iter()
+            .map(|related| (related.tag, related.questions))
+            .collect()
+    }
+
+    #[test]
+    fn pairs_are_counted_in_both_directions() {
+        let mut matrix = CoOccurrenceMatrix::new();
+        matrix.add_tags(&tags(&["rust", "rayon", "rust"]));
+        matrix.add_tags(&tags(&["rayon", "rust", "threads"]));
+        assert_eq!(matrix.count("rust", "rayon"), 2);
+        assert_eq!(matrix.count("rayon", "rust"), 2);
+        assert_eq!(matrix.count("rust", "rust"), 0);
+        assert_eq!(matrix.count("rust", "python"), 0);
+        assert_eq!(matrix.pairs(), 4);
+    }
+
+    #[test]
+    fn related_tags_are_ranked_by_shared_questions_and_name() {
+        let mut matrix = CoOccurrenceMatrix::new();
+        matrix.add_tags(&tags(&["rust", "rayon"]));
+        matrix.add_tags(&tags(&["rust", "rayon"]));
+        matrix.add_tags(&tags(&["rust", "serde"]));
+        matrix.add_tags(&tags(&["rust", "async"]));
+        assert_eq!(
+            names(matrix.related_tags("rust", 2)),
+            vec![("rayon".to_string(), 2), ("async".to_string(), 1)]
+        );
+        assert!(matrix.related_tags("python", 2).is_empty());
+    }
+
+    #[test]
+    fn merged_matrices_count_every_question() {
+        let mut first = CoOccurrenceMatrix::new();
+        first.add_tags(&tags(&["a", "b"]));
+        let mut second = CoOccurrenceMatrix::new();
+        second.add_tags(&tags(&["a", "b", "c"]));
+
+        let mut by_reference = first.clone();
+        by_reference.merge_ref(&second);
+        first.merge(second);
+
+        assert_eq!(first, by_reference);
+        assert_eq!(first.count("b", "a"), 2);
+        assert_eq!(first.count("c", "a"), 1);
+        assert_eq!(first.generate_report(1)["c"][0].tag, "a");
+    }
+}
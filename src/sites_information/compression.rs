@@ -0,0 +1,290 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use flate2::read::MultiGzDecoder;
+
+const JSONL_EXTENSION: &str = ".jsonl";
+/// Name of the `Posts.xml` files of the data dump, compared ignoring the case.
+const POSTS_XML_NAME: &str = "posts.xml";
+/// Characters that may separate the name of a site from `Posts.xml` in a file name.
+const POSTS_XML_SEPARATORS: [char; 2] = ['-', '_'];
+const GZIP_EXTENSION: &str = ".gz";
+#[cfg(feature = "zstd")]
+const ZSTD_EXTENSION: &str = ".zst";
+#[cfg(feature = "bzip2")]
+const BZIP2_EXTENSION: &str = ".bz2";
+
+/// Enum that represents the format of the data of a file with questions, once decompressed.
+///
+/// # Variants
+///
+/// * `JsonLines` - A JSON question per line, in the shape of the input schema.
+/// * `PostsXml` - The `Posts.xml` file of the official StackExchange data dump, with a `row`
+///   element per post. Only the questions are used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    JsonLines,
+    PostsXml,
+}
+
+impl FileFormat {
+    /// Returns the format of the file in the provided path based on its name, ignoring the
+    /// compression extension. Only the `Posts.xml` files of the data dump, optionally prefixed by
+    /// the name of the site (`site-Posts.xml` or `site_Posts.xml`), are read as XML, so the other
+    /// files of the dump, such as `Users.xml`, are ignored. If the file is not a supported file,
+    /// it returns None.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// The format of the file, if it is supported.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        let (stem, _) = split_compression(file_name);
+        if stem.ends_with(JSONL_EXTENSION) {
+            Some(FileFormat::JsonLines)
+        } else if is_posts_xml(stem) {
+            Some(FileFormat::PostsXml)
+        } else {
+            None
+        }
+    }
+}
+
+/// Enum that represents the compression of a file with questions.
+///
+/// # Variants
+///
+/// * `Plain` - The file is not compressed.
+/// * `Gzip` - The file is compressed with gzip.
+/// * `Zstd` - The file is compressed with zstd. Requires the `zstd` feature.
+/// * `Bzip2` - The file is compressed with bzip2. Requires the `bzip2` feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Plain,
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "bzip2")]
+    Bzip2,
+}
+
+impl Compression {
+    /// Returns the compression of the file in the provided path based on its extension.
+    /// If the file is not a supported jsonl or xml file, it returns None.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// The compression of the file, if it is supported.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        FileFormat::from_path(path)?;
+        let file_name = path.file_name()?.to_str()?;
+        Some(split_compression(file_name).1)
+    }
+
+    /// Wraps the provided reader so that the data is decompressed while it is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader with the raw data of the file.
+    ///
+    /// # Returns
+    ///
+    /// A buffered reader with the decompressed data.
+    pub fn decompress<R: Read + Send + 'static>(
+        self,
+        reader: R,
+    ) -> std::io::Result<Box<dyn BufRead + Send>> {
+        let reader: Box<dyn BufRead + Send> = match self {
+            Compression::Plain => Box::new(BufReader::new(reader)),
+            Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(reader)?)),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => {
+                Box::new(BufReader::new(bzip2::read::MultiBzDecoder::new(reader)))
+            }
+        };
+        Ok(reader)
+    }
+}
+
+/// Splits the compression extension from the end of a file name.
+///
+/// # Arguments
+///
+/// * `file_name` - The name of the file.
+///
+/// # Returns
+///
+/// The name without the compression extension and the compression it indicates.
+fn split_compression(file_name: &str) -> (&str, Compression) {
+    if let Some(stem) = file_name.strip_suffix(GZIP_EXTENSION) {
+        return (stem, Compression::Gzip);
+    }
+    #[cfg(feature = "zstd")]
+    if let Some(stem) = file_name.strip_suffix(ZSTD_EXTENSION) {
+        return (stem, Compression::Zstd);
+    }
+    #[cfg(feature = "bzip2")]
+    if let Some(stem) = file_name.strip_suffix(BZIP2_EXTENSION) {
+        return (stem, Compression::Bzip2);
+    }
+    (file_name, Compression::Plain)
+}
+
+/// Returns whether the provided file name, without compression extension, is the name of a
+/// `Posts.xml` file, ignoring the case.
+///
+/// # Arguments
+///
+/// * `name` - The name of the file.
+///
+/// # Returns
+///
+/// Whether the file holds the posts of a data dump.
+fn is_posts_xml(name: &str) -> bool {
+    let name = name.to_lowercase();
+    match name.strip_suffix(POSTS_XML_NAME) {
+        Some(prefix) => prefix.is_empty() || prefix.ends_with(POSTS_XML_SEPARATORS),
+        None => false,
+    }
+}
+
+/// Opens the file in the provided path and returns a reader that decompresses it on the fly.
+/// If the file could not be opened or its extension is not supported, it returns an error.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+///
+/// # Returns
+///
+/// A result with a buffered reader of the decompressed data of the file.
+pub fn open_file(path: &Path) -> std::io::Result<Box<dyn BufRead + Send>> {
+    let compression = match Compression::from_path(path) {
+        Some(compression) => compression,
+        None => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Unsupported file extension",
+            ))
+        }
+    };
+    compression.decompress(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use flate2::{write::GzEncoder, Compression as GzLevel};
+    use std::io::Write;
+
+    #[test]
+    fn compression_from_extension() {
+        assert_eq!(
+            Compression::from_path(Path::new("data/site.jsonl")),
+            Some(Compression::Plain)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("data/site.jsonl.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(Compression::from_path(Path::new("data/site.json")), None);
+        assert_eq!(Compression::from_path(Path::new("data/site.gz")), None);
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            FileFormat::from_path(Path::new("data/site.jsonl.gz")),
+            Some(FileFormat::JsonLines)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("dump/site/Posts.xml")),
+            Some(FileFormat::PostsXml)
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("dump/site/Posts.xml.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(FileFormat::from_path(Path::new("dump/site/Posts.7z")), None);
+    }
+
+    #[test]
+    fn only_posts_xml_files_are_xml() {
+        for name in [
+            "posts.xml",
+            "POSTS.XML",
+            "site-Posts.xml",
+            "site_posts.xml.gz",
+        ] {
+            assert_eq!(
+                FileFormat::from_path(Path::new(name)),
+                Some(FileFormat::PostsXml),
+                "{}",
+                name
+            );
+        }
+        for name in [
+            "Users.xml",
+            "Comments.xml.gz",
+            "site-Tags.xml",
+            "reposts.xml",
+            "site.xml",
+        ] {
+            assert_eq!(FileFormat::from_path(Path::new(name)), None, "{}", name);
+            assert_eq!(Compression::from_path(Path::new(name)), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn gzip_data_is_decompressed() {
+        let mut encoder = GzEncoder::new(Vec::new(), GzLevel::default());
+        encoder.write_all(b"first line\nsecond line\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let reader = Compression::Gzip
+            .decompress(std::io::Cursor::new(compressed))
+            .unwrap();
+        let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+        assert_eq!(lines, vec!["first line", "second line"]);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_data_is_decompressed() {
+        let compressed = zstd::encode_all(&b"first line\nsecond line\n"[..], 0).unwrap();
+
+        let reader = Compression::Zstd
+            .decompress(std::io::Cursor::new(compressed))
+            .unwrap();
+        let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+        assert_eq!(lines, vec!["first line", "second line"]);
+    }
+
+    #[cfg(feature = "bzip2")]
+    #[test]
+    fn bzip2_data_is_decompressed() {
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(b"first line\nsecond line\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let reader = Compression::Bzip2
+            .decompress(std::io::Cursor::new(compressed))
+            .unwrap();
+        let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+        assert_eq!(lines, vec!["first line", "second line"]);
+    }
+}
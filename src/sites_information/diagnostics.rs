@@ -0,0 +1,348 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::report::DiagnosticsReport;
+
+/// Maximum amount of line errors kept in detail for each file. The rest are only counted.
+const MAX_REPORTED_ERRORS_PER_FILE: usize = 100;
+
+/// Enum that represents the kind of problem found in a line of a file.
+///
+/// # Variants
+///
+/// * `UnreadableLine` - The line could not be read from the file (for example, invalid UTF-8).
+/// * `MalformedLine` - The line was read but it is not a valid question.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    UnreadableLine,
+    MalformedLine,
+}
+
+impl DiagnosticKind {
+    /// Returns the name of the kind used in the reports.
+    ///
+    /// # Returns
+    ///
+    /// The name of the kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticKind::UnreadableLine => "unreadable_line",
+            DiagnosticKind::MalformedLine => "malformed_line",
+        }
+    }
+}
+
+/// Represents a line of a file that was skipped.
+///
+/// # Attributes
+///
+/// * `line` - The number of the line, starting from 1.
+/// * `kind` - The kind of problem found in the line.
+/// * `message` - The description of the problem.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineDiagnostic {
+    pub line: u64,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+impl LineDiagnostic {
+    /// Creates a new line diagnostic.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The number of the line, starting from 1.
+    /// * `kind` - The kind of problem found in the line.
+    /// * `message` - The description of the problem.
+    ///
+    /// # Returns
+    ///
+    /// A new line diagnostic instance.
+    pub fn new(line: u64, kind: DiagnosticKind, message: String) -> Self {
+        LineDiagnostic {
+            line,
+            kind,
+            message,
+        }
+    }
+}
+
+/// Represents the problems found while reading one file.
+///
+/// # Attributes
+///
+/// * `file` - The path of the file.
+/// * `file_error` - The error that prevented the file from being read, if any.
+/// * `skipped_lines` - The amount of lines of the file that were skipped.
+/// * `errors` - The first line errors of the file, ordered by line number.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileDiagnostics {
+    pub file: String,
+    #[serde(rename = "error", default, skip_serializing_if = "Option::is_none")]
+    pub file_error: Option<String>,
+    pub skipped_lines: u64,
+    pub errors: Vec<LineDiagnostic>,
+}
+
+impl FileDiagnostics {
+    /// Creates the diagnostics of a file without problems.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// A new file diagnostics instance.
+    pub fn new(file: String) -> Self {
+        FileDiagnostics {
+            file,
+            file_error: None,
+            skipped_lines: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Creates the diagnostics of a file that could not be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path of the file.
+    /// * `error` - The description of the error.
+    ///
+    /// # Returns
+    ///
+    /// A new file diagnostics instance.
+    pub fn unreadable(file: String, error: String) -> Self {
+        FileDiagnostics {
+            file_error: Some(error),
+            ..Self::new(file)
+        }
+    }
+
+    /// Adds a skipped line to the diagnostics of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The problem found in the line.
+    pub fn add_line_error(&mut self, error: LineDiagnostic) {
+        self.skipped_lines += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS_PER_FILE {
+            self.errors.push(error);
+        }
+    }
+
+    /// Merges the diagnostics of the file with the diagnostics of another part of the same file.
+    /// Only the errors with the lowest line numbers are kept in detail.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other diagnostics to merge with
+    pub fn merge(&mut self, other: Self) {
+        self.skipped_lines += other.skipped_lines;
+        if self.file_error.is_none() {
+            self.file_error = other.file_error;
+        }
+        self.errors.extend(other.errors);
+        self.errors.sort_by_key(|error| error.line);
+        self.errors.truncate(MAX_REPORTED_ERRORS_PER_FILE);
+    }
+
+    /// Shifts the line numbers of the errors, used when the diagnostics belong to a part of the
+    /// file that does not start at its first line.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The amount of lines of the file before the part.
+    pub fn offset_lines(&mut self, offset: u64) {
+        for error in &mut self.errors {
+            error.line += offset;
+        }
+    }
+
+    /// Returns whether any problem was found in the file.
+    ///
+    /// # Returns
+    ///
+    /// True if the file could not be read or if any line was skipped.
+    pub fn has_problems(&self) -> bool {
+        self.file_error.is_some() || self.skipped_lines > 0
+    }
+}
+
+/// Represents the problems found while loading a collection of sites.
+/// Only the files with problems are kept.
+///
+/// # Attributes
+///
+/// * `files` - The diagnostics of the files with problems, ordered by path.
+/// * `merged_sites` - The paths of the files of each site that was stored in more than one file, by name.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Diagnostics {
+    files: Vec<FileDiagnostics>,
+    merged_sites: BTreeMap<String, Vec<String>>,
+}
+
+impl Diagnostics {
+    /// Creates an empty diagnostics report.
+    ///
+    /// # Returns
+    ///
+    /// A new diagnostics instance.
+    pub fn new() -> Self {
+        Diagnostics::default()
+    }
+
+    /// Adds the diagnostics of a file to the report, if the file had any problem.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The diagnostics of the file.
+    pub fn add_file(&mut self, file: FileDiagnostics) {
+        if file.has_problems() {
+            let index = self.files.partition_point(|other| other.file < file.file);
+            self.files.insert(index, file);
+        }
+    }
+
+    /// Merges the diagnostics with the diagnostics of other files.
+    /// It takes ownership of the other diagnostics, making them unusable after the merge.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other diagnostics to merge with
+    pub fn merge(&mut self, other: Self) {
+        for file in other.files {
+            self.add_file(file);
+        }
+        self.merged_sites.extend(other.merged_sites);
+    }
+
+    /// Records that a site was stored in more than one file, whose information was merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The name of the site.
+    /// * `files` - The paths of the files of the site.
+    pub fn add_merged_site(&mut self, site: String, files: Vec<String>) {
+        self.merged_sites.insert(site, files);
+    }
+
+    /// Returns the paths of the files of each site that was stored in more than one file.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the files of the merged sites, by name.
+    pub fn merged_sites(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.merged_sites
+    }
+
+    /// Returns the diagnostics of the files with problems, ordered by path.
+    ///
+    /// # Returns
+    ///
+    /// The diagnostics of the files with problems.
+    pub fn files(&self) -> &[FileDiagnostics] {
+        &self.files
+    }
+
+    /// Returns the amount of files that could not be read.
+    ///
+    /// # Returns
+    ///
+    /// The amount of skipped files.
+    pub fn skipped_files(&self) -> u64 {
+        self.files
+            .iter()
+            .filter(|file| file.file_error.is_some())
+            .count() as u64
+    }
+
+    /// Returns the amount of lines that were skipped in all the files.
+    ///
+    /// # Returns
+    ///
+    /// The amount of skipped lines.
+    pub fn skipped_lines(&self) -> u64 {
+        self.files.iter().map(|file| file.skipped_lines).sum()
+    }
+
+    /// Returns whether no problem was found.
+    ///
+    /// # Returns
+    ///
+    /// True if every file and every line could be used.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Generates the report of the problems found.
+    ///
+    /// # Returns
+    ///
+    /// The diagnostics report.
+    pub fn generate_report(&self) -> DiagnosticsReport {
+        DiagnosticsReport {
+            files: self.files.clone(),
+            merged_sites: self.merged_sites.clone(),
+            skipped_files: self.skipped_files(),
+            skipped_lines: self.skipped_lines(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn malformed(line: u64) -> LineDiagnostic {
+        LineDiagnostic::new(line, DiagnosticKind::MalformedLine, "error".to_string())
+    }
+
+    #[test]
+    fn file_merge_keeps_lowest_lines() {
+        let mut first = FileDiagnostics::new("file".to_string());
+        first.add_line_error(malformed(7));
+        let mut second = FileDiagnostics::new("file".to_string());
+        second.add_line_error(malformed(3));
+
+        first.merge(second);
+
+        assert_eq!(first.skipped_lines, 2);
+        assert_eq!(first.errors, vec![malformed(3), malformed(7)]);
+    }
+
+    #[test]
+    fn detailed_errors_are_limited() {
+        let mut file = FileDiagnostics::new("file".to_string());
+        for line in 0..(MAX_REPORTED_ERRORS_PER_FILE as u64 + 5) {
+            file.add_line_error(malformed(line));
+        }
+        assert_eq!(file.skipped_lines, MAX_REPORTED_ERRORS_PER_FILE as u64 + 5);
+        assert_eq!(file.errors.len(), MAX_REPORTED_ERRORS_PER_FILE);
+    }
+
+    #[test]
+    fn only_files_with_problems_are_kept() {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.add_file(FileDiagnostics::new("ok".to_string()));
+        diagnostics.add_file(FileDiagnostics::unreadable(
+            "missing".to_string(),
+            "not found".to_string(),
+        ));
+        let mut with_errors = FileDiagnostics::new("errors".to_string());
+        with_errors.add_line_error(malformed(1));
+        diagnostics.add_file(with_errors);
+
+        assert_eq!(diagnostics.files().len(), 2);
+        assert_eq!(diagnostics.files()[0].file, "errors");
+        assert_eq!(diagnostics.skipped_files(), 1);
+        assert_eq!(diagnostics.skipped_lines(), 1);
+
+        let report = diagnostics.generate_report();
+        assert_eq!(report.skipped_files, 1);
+        assert_eq!(report.files[1].file_error, Some("not found".to_string()));
+    }
+}
@@ -0,0 +1,362 @@
+use std::io::{self, Write};
+
+use super::{
+    ranking::{compare_entries, words_questions_ratio, RankingOrder},
+    report::{Report, TagReport},
+};
+
+/// Enum that represents the format of the exported tables.
+///
+/// # Variants
+///
+/// * `Csv` - Comma separated values. Fields with commas, quotes or line breaks are quoted.
+/// * `Tsv` - Tab separated values. Fields with tabs, quotes or line breaks are quoted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+}
+
+impl TableFormat {
+    /// Returns the character that separates the fields of a row.
+    ///
+    /// # Returns
+    ///
+    /// The delimiter of the format.
+    pub fn delimiter(&self) -> char {
+        match self {
+            TableFormat::Csv => ',',
+            TableFormat::Tsv => '\t',
+        }
+    }
+
+    /// Returns the extension of the files written in the format.
+    ///
+    /// # Returns
+    ///
+    /// The extension, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Tsv => "tsv",
+        }
+    }
+
+    /// Escapes a field so it can be written in a row.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The value of the field.
+    ///
+    /// # Returns
+    ///
+    /// The field, quoted if it contains the delimiter, a quote or a line break.
+    fn escape(&self, field: &str) -> String {
+        let needs_quotes = field
+            .chars()
+            .any(|c| c == self.delimiter() || c == '"' || c == '\n' || c == '\r');
+        if needs_quotes {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+/// Represents a table of the report, ready to be exported.
+///
+/// # Attributes
+///
+/// * `name` - The name of the table, used as the name of its file.
+/// * `header` - The names of the columns.
+/// * `rows` - The values of each row, in the order of the columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: &'static str,
+    pub header: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Writes the header and the rows of the table in the provided format.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the table is written.
+    /// * `format` - The format of the table.
+    ///
+    /// # Returns
+    ///
+    /// A result that could carry the error found while writing.
+    pub fn write<W: Write>(&self, writer: &mut W, format: TableFormat) -> io::Result<()> {
+        let header: Vec<String> = self
+            .header
+            .iter()
+            .map(|column| column.to_string())
+            .collect();
+        write_row(writer, format, &header)?;
+        for row in &self.rows {
+            write_row(writer, format, row)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the file where the table is written in the provided format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format of the table.
+    ///
+    /// # Returns
+    ///
+    /// The name of the file.
+    pub fn file_name(&self, format: TableFormat) -> String {
+        format!("{}.{}", self.name, format.extension())
+    }
+}
+
+/// Writes a row of a table, ending it with a line break.
+fn write_row<W: Write>(writer: &mut W, format: TableFormat, row: &[String]) -> io::Result<()> {
+    let fields: Vec<String> = row.iter().map(|field| format.escape(field)).collect();
+    writeln!(writer, "{}", fields.join(&format.delimiter().to_string()))
+}
+
+/// Builds the rows of a ranked table. The entries are ranked by their ratio of words/questions,
+/// from the highest to the lowest, and ties are broken by name. Entries without questions go last,
+/// without ratio nor rank.
+///
+/// # Arguments
+///
+/// * `entries` - The name, questions and words of each entry.
+/// * `prefix` - The fields written before the name in every row.
+///
+/// # Returns
+///
+/// The rows with the prefix, name, questions, words, ratio and rank of each entry.
+fn ranked_rows(mut entries: Vec<(&str, u64, u64)>, prefix: &[String]) -> Vec<Vec<String>> {
+    entries.sort_by(|a, b| {
+        compare_entries(
+            (a.0, words_questions_ratio(a.2, a.1).unwrap_or(f64::NAN)),
+            (b.0, words_questions_ratio(b.2, b.1).unwrap_or(f64::NAN)),
+            RankingOrder::Descending,
+        )
+    });
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, questions, words))| {
+            let mut row = prefix.to_vec();
+            row.push(name.to_string());
+            row.push(questions.to_string());
+            row.push(words.to_string());
+            match words_questions_ratio(words, questions) {
+                Some(ratio) => {
+                    row.push(format!("{:.4}", ratio));
+                    row.push((index + 1).to_string());
+                }
+                None => {
+                    row.push(String::new());
+                    row.push(String::new());
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+/// Returns the name, questions and words of each tag.
+fn tag_entries<'a, I>(tags: I) -> Vec<(&'a str, u64, u64)>
+where
+    I: IntoIterator<Item = (&'a String, &'a TagReport)>,
+{
+    tags.into_iter()
+        .map(|(tag, report)| (tag.as_str(), report.questions, report.words))
+        .collect()
+}
+
+/// Builds the table with one row per site, ranked by their ratio of words/questions.
+///
+/// # Arguments
+///
+/// * `report` - The report of the sites.
+///
+/// # Returns
+///
+/// The table of the sites.
+pub fn sites_table(report: &Report) -> Table {
+    let entries = report
+        .sites
+        .iter()
+        .map(|(site, site_report)| (site.as_str(), site_report.questions, site_report.words))
+        .collect();
+    Table {
+        name: "sites",
+        header: vec!["site", "questions", "words", "ratio", "rank"],
+        rows: ranked_rows(entries, &[]),
+    }
+}
+
+/// Builds the table with one row per tag of all the sites, ranked by their ratio of words/questions.
+///
+/// # Arguments
+///
+/// * `report` - The report of the sites.
+///
+/// # Returns
+///
+/// The table of the tags.
+pub fn tags_table(report: &Report) -> Table {
+    Table {
+        name: "tags",
+        header: vec!["tag", "questions", "words", "ratio", "rank"],
+        rows: ranked_rows(tag_entries(&report.tags), &[]),
+    }
+}
+
+/// Builds the table with one row per tag of each site. The tags are ranked within their site.
+///
+/// # Arguments
+///
+/// * `report` - The report of the sites.
+///
+/// # Returns
+///
+/// The table of the tags of each site.
+pub fn site_tags_table(report: &Report) -> Table {
+    let rows = report
+        .sites
+        .iter()
+        .flat_map(|(site, site_report)| {
+            ranked_rows(tag_entries(&site_report.tags), std::slice::from_ref(site))
+        })
+        .collect();
+    Table {
+        name: "site_tags",
+        header: vec!["site", "tag", "questions", "words", "ratio", "rank"],
+        rows,
+    }
+}
+
+/// Builds every table of the report: sites, tags and tags of each site.
+///
+/// # Arguments
+///
+/// * `report` - The report of the sites.
+///
+/// # Returns
+///
+/// The tables of the report.
+pub fn report_tables(report: &Report) -> Vec<Table> {
+    vec![
+        sites_table(report),
+        tags_table(report),
+        site_tags_table(report),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::sites_information::report::{FieldsReport, SiteReport, TotalsReport};
+
+    fn tag(questions: u64, words: u64) -> TagReport {
+        TagReport {
+            distribution: None,
+            fields: FieldsReport::default(),
+            questions,
+            words,
+        }
+    }
+
+    fn sample_report() -> Report {
+        let first = SiteReport {
+            chatty_tags: Vec::new(),
+            distribution: None,
+            fields: FieldsReport::default(),
+            questions: 2,
+            tags: BTreeMap::from([
+                ("a,b".to_string(), tag(1, 10)),
+                ("c".to_string(), tag(2, 30)),
+            ]),
+            words: 30,
+        };
+        let second = SiteReport {
+            chatty_tags: Vec::new(),
+            distribution: None,
+            fields: FieldsReport::default(),
+            questions: 1,
+            tags: BTreeMap::from([("c".to_string(), tag(1, 20))]),
+            words: 20,
+        };
+        Report {
+            diagnostics: None,
+            leaderboards: BTreeMap::new(),
+            padron: "108225".to_string(),
+            related_tags: None,
+            sites: BTreeMap::from([("first".to_string(), first), ("second".to_string(), second)]),
+            tags: BTreeMap::from([
+                ("a,b".to_string(), tag(1, 10)),
+                ("c".to_string(), tag(3, 50)),
+            ]),
+            totals: TotalsReport {
+                chatty_sites: Vec::new(),
+                chatty_tags: Vec::new(),
+                empty_sites: Vec::new(),
+            },
+        }
+    }
+
+    fn to_string(table: &Table, format: TableFormat) -> String {
+        let mut buffer = Vec::new();
+        table.write(&mut buffer, format).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn sites_are_ranked_by_ratio() {
+        let table = sites_table(&sample_report());
+        assert_eq!(
+            to_string(&table, TableFormat::Csv),
+            "site,questions,words,ratio,rank\nsecond,1,20,20.0000,1\nfirst,2,30,15.0000,2\n"
+        );
+    }
+
+    #[test]
+    fn fields_with_the_delimiter_are_quoted() {
+        let table = tags_table(&sample_report());
+        let csv = to_string(&table, TableFormat::Csv);
+        assert!(csv.contains("\"a,b\",1,10,10.0000,2\n"));
+        let tsv = to_string(&table, TableFormat::Tsv);
+        assert!(tsv.contains("a,b\t1\t10\t10.0000\t2\n"));
+    }
+
+    #[test]
+    fn site_tags_are_ranked_within_their_site() {
+        let table = site_tags_table(&sample_report());
+        let ranks: Vec<(&str, &str, &str)> = table
+            .rows
+            .iter()
+            .map(|row| (row[0].as_str(), row[1].as_str(), row[5].as_str()))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![
+                ("first", "c", "1"),
+                ("first", "a,b", "2"),
+                ("second", "c", "1")
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_are_broken_by_name_and_empty_entries_go_last() {
+        let rows = ranked_rows(vec![("b", 1, 5), ("empty", 0, 0), ("a", 2, 10)], &[]);
+        let names: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "empty"]);
+        assert_eq!(rows[2][3], "");
+        assert_eq!(rows[2][4], "");
+    }
+}
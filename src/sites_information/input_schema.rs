@@ -0,0 +1,283 @@
+use serde::{de::Error, Deserialize};
+
+use super::{
+    question::Question,
+    question_information::QuestionTexts,
+    text_layout::{TextField, TextLayout},
+};
+
+/// Value of the `PostTypeId` of the questions in the StackExchange dumps.
+const QUESTION_POST_TYPE: u64 = 1;
+
+/// Enum that represents the shape of the JSON questions of the lines of the files.
+/// Fields that are not used, such as ids and dates, are ignored in every schema.
+///
+/// # Variants
+///
+/// * `Texts` - `{"texts": [...], "tags": [...]}`, where the field of each text is given by the
+///   text layout. It is the shape of the flax-sentence-embeddings datasets.
+/// * `Fields` - `{"title": ..., "body": ..., "tags": ..., "answers": [...]}`, where the tags are a
+///   list or a `<a><b>` or `|a|b|` string, and the answers, which count as other texts, are strings
+///   or objects with a `body`. The title or the body, and the tags, are required.
+/// * `Posts` - The rows of the `Posts` table of the StackExchange dumps, with `PostTypeId`, `Title`,
+///   `Body` and `Tags` fields. Only the questions are used; the other posts are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum InputSchema {
+    #[default]
+    Texts,
+    Fields,
+    Posts,
+}
+
+impl InputSchema {
+    /// Returns the schema with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the schema: texts, fields or posts.
+    ///
+    /// # Returns
+    ///
+    /// The schema, or None if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "texts" => Some(InputSchema::Texts),
+            "fields" => Some(InputSchema::Fields),
+            "posts" => Some(InputSchema::Posts),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the schema, used to detect when the questions were read differently.
+    ///
+    /// # Returns
+    ///
+    /// The name of the schema.
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputSchema::Texts => "texts",
+            InputSchema::Fields => "fields",
+            InputSchema::Posts => "posts",
+        }
+    }
+
+    /// Parses a line with a JSON question of this schema into the texts of the question.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line with the question.
+    /// * `layout` - The field held by each of the texts, used by the `Texts` schema.
+    ///
+    /// # Returns
+    ///
+    /// A result with the texts of the question, or None if the line holds a post that is not
+    /// a question, or the error found while parsing it.
+    pub fn parse(
+        &self,
+        line: &str,
+        layout: &TextLayout,
+    ) -> serde_json::Result<Option<QuestionTexts>> {
+        match self {
+            InputSchema::Texts => {
+                let question: Question = serde_json::from_str(line)?;
+                Ok(Some(question.into_texts(layout)))
+            }
+            InputSchema::Fields => {
+                let question: FieldsQuestion = serde_json::from_str(line)?;
+                if question.title.is_none() && question.body.is_none() {
+                    return Err(serde_json::Error::custom("missing field `title` or `body`"));
+                }
+                let answers = question.answers.into_iter().map(|answer| match answer {
+                    Answer::Text(text) => (TextField::Other, text),
+                    Answer::Post { body } => (TextField::Other, body),
+                });
+                let texts = [
+                    (TextField::Title, question.title.unwrap_or_default()),
+                    (TextField::Body, question.body.unwrap_or_default()),
+                ];
+                Ok(Some(QuestionTexts {
+                    texts: texts.into_iter().chain(answers).collect(),
+                    tags: question.tags.into_list(),
+                }))
+            }
+            InputSchema::Posts => {
+                let post: Post = serde_json::from_str(line)?;
+                Ok(post.into_texts())
+            }
+        }
+    }
+}
+
+/// Represents the tags of a question, as a list or as a single string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Tags {
+    List(Vec<String>),
+    Markup(String),
+}
+
+impl Tags {
+    /// Returns the tags as a list. The strings such as `<a><b>` or `|a|b|` are split into tags.
+    fn into_list(self) -> Vec<String> {
+        match self {
+            Tags::List(tags) => tags,
+            Tags::Markup(tags) => tags
+                .split(['<', '>', '|'])
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Represents an answer of a question, as its text or as an object with its body.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Text(String),
+    Post { body: String },
+}
+
+/// Represents a question with a field for each of its texts. It must have a title or a body.
+#[derive(Debug, Deserialize)]
+struct FieldsQuestion {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    tags: Tags,
+    #[serde(default)]
+    answers: Vec<Answer>,
+}
+
+/// Represents an id that the dumps write as a number or as a string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Id {
+    Number(u64),
+    Text(String),
+}
+
+impl Id {
+    /// Returns the numeric value of the id, if it is a number.
+    fn value(&self) -> Option<u64> {
+        match self {
+            Id::Number(value) => Some(*value),
+            Id::Text(value) => value.trim().parse().ok(),
+        }
+    }
+}
+
+/// Represents a row of the `Posts` table of the StackExchange dumps, read from a JSON line or from
+/// the attributes of a `row` element of a `Posts.xml` file.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Post {
+    post_type_id: Id,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    tags: Option<Tags>,
+}
+
+impl Post {
+    /// Creates a post from the values of its attributes, as they are written in the dumps.
+    ///
+    /// # Arguments
+    ///
+    /// * `post_type_id` - The type of the post, `1` for the questions.
+    /// * `title` - The title of the post.
+    /// * `body` - The body of the post.
+    /// * `tags` - The tags of the post, as a `<a><b>` or `|a|b|` string, if it has any.
+    ///
+    /// # Returns
+    ///
+    /// A new Post instance.
+    pub fn new(post_type_id: String, title: String, body: String, tags: Option<String>) -> Self {
+        Post {
+            post_type_id: Id::Text(post_type_id),
+            title,
+            body,
+            tags: tags.map(Tags::Markup),
+        }
+    }
+
+    /// Converts the post into the texts of its question.
+    ///
+    /// # Returns
+    ///
+    /// The texts and the tags of the question, or None if the post is not a question.
+    pub fn into_texts(self) -> Option<QuestionTexts> {
+        if self.post_type_id.value() != Some(QUESTION_POST_TYPE) {
+            return None;
+        }
+        Some(QuestionTexts {
+            texts: vec![(TextField::Title, self.title), (TextField::Body, self.body)],
+            tags: self.tags.map(Tags::into_list).unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites_information::{
+        question_information::{FieldWords, QuestionInformation},
+        tokenizer::WhitespaceTokenizer,
+    };
+
+    fn parse(schema: InputSchema, line: &str) -> Option<QuestionInformation> {
+        schema
+            .parse(line, &TextLayout::default())
+            .unwrap()
+            .map(|question| question.into_information(&WhitespaceTokenizer))
+    }
+
+    #[test]
+    fn fields_are_read_by_name() {
+        let line = r#"{"id": 7, "creation_date": "2020-01-01", "title": "A title", "body": "The body text", "tags": "<rust><rayon>", "answers": ["One answer", {"body": "Another one here"}]}"#;
+        let question = parse(InputSchema::Fields, line).unwrap();
+        assert_eq!(question.words, 10);
+        assert_eq!(question.fields, FieldWords { title: 2, body: 3 });
+        assert_eq!(question.tags, vec!["rust", "rayon"]);
+
+        let question = parse(
+            InputSchema::Fields,
+            r#"{"body": "Only body", "tags": ["a"]}"#,
+        );
+        assert_eq!(question.unwrap().fields, FieldWords { title: 0, body: 2 });
+    }
+
+    #[test]
+    fn only_the_questions_of_the_posts_are_read() {
+        let line = r#"{"Id": "1", "PostTypeId": "1", "Title": "A title", "Body": "<p>Body</p>", "Tags": "|rust|serde|"}"#;
+        let question = parse(InputSchema::Posts, line).unwrap();
+        assert_eq!(question.words, 3);
+        assert_eq!(question.tags, vec!["rust", "serde"]);
+
+        let answer = r#"{"Id": 2, "PostTypeId": 2, "Body": "An answer"}"#;
+        assert!(parse(InputSchema::Posts, answer).is_none());
+    }
+
+    #[test]
+    fn lines_of_another_schema_are_errors() {
+        let line = r#"{"texts": ["A title"], "tags": ["a"]}"#;
+        assert!(parse(InputSchema::Texts, line).is_some());
+        assert!(InputSchema::Fields
+            .parse(line, &TextLayout::default())
+            .is_err());
+        assert!(InputSchema::Posts
+            .parse(line, &TextLayout::default())
+            .is_err());
+    }
+
+    #[test]
+    fn schemas_are_selected_by_name() {
+        for schema in [InputSchema::Texts, InputSchema::Fields, InputSchema::Posts] {
+            assert_eq!(InputSchema::from_name(schema.name()), Some(schema));
+        }
+        assert_eq!(InputSchema::from_name("xml"), None);
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::BTreeSet,
+    fs::{read_dir, ReadDir},
+    path::{Path, PathBuf},
+};
+
+use glob::Pattern;
+
+use super::{compression::Compression, parsing_error::ParsingError};
+
+/// Represents the files that are loaded into a collection of sites.
+///
+/// # Attributes
+///
+/// * `directories` - The directories whose supported jsonl and xml files are loaded.
+/// * `files` - The files that are loaded regardless of the patterns.
+/// * `recursive` - Whether the subdirectories of the directories are also browsed.
+/// * `include` - The patterns a file found in a directory must match to be loaded. If there are none, every file is loaded.
+/// * `exclude` - The patterns a file found in a directory must not match to be loaded.
+#[derive(Debug, Clone, Default)]
+pub struct InputSelection {
+    directories: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    recursive: bool,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl InputSelection {
+    /// Creates an empty selection.
+    ///
+    /// # Returns
+    ///
+    /// A new InputSelection instance.
+    pub fn new() -> Self {
+        InputSelection::default()
+    }
+
+    /// Adds a directory whose supported jsonl and xml files are loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The path of the directory.
+    ///
+    /// # Returns
+    ///
+    /// The selection with the directory added.
+    pub fn directory<P: AsRef<Path>>(mut self, directory: P) -> Self {
+        self.directories.push(directory.as_ref().to_path_buf());
+        self
+    }
+
+    /// Adds a file that is loaded regardless of the include and exclude patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// The selection with the file added.
+    pub fn file<P: AsRef<Path>>(mut self, file: P) -> Self {
+        self.files.push(file.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets whether the subdirectories of the directories are also browsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `recursive` - Whether the subdirectories are browsed.
+    ///
+    /// # Returns
+    ///
+    /// The selection with the recursion set.
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Adds a glob pattern that the files found in the directories must match to be loaded.
+    /// The pattern is checked against the file name and against the path relative to the directory.
+    /// If the pattern is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The glob pattern.
+    ///
+    /// # Returns
+    ///
+    /// A result with the selection with the pattern added or a ParsingError.
+    pub fn include(mut self, pattern: &str) -> Result<Self, ParsingError> {
+        self.include.push(compile_pattern(pattern)?);
+        Ok(self)
+    }
+
+    /// Adds a glob pattern that excludes the files found in the directories that match it.
+    /// The pattern is checked against the file name and against the path relative to the directory.
+    /// If the pattern is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The glob pattern.
+    ///
+    /// # Returns
+    ///
+    /// A result with the selection with the pattern added or a ParsingError.
+    pub fn exclude(mut self, pattern: &str) -> Result<Self, ParsingError> {
+        self.exclude.push(compile_pattern(pattern)?);
+        Ok(self)
+    }
+
+    /// Returns the paths of the selected files, sorted and without duplicates.
+    /// If a directory could not be opened or no files are found, it returns an error.
+    ///
+    /// # Returns
+    ///
+    /// A result that could carry a vector of PathBuf or a ParsingError.
+    pub fn files_paths(&self) -> Result<Vec<PathBuf>, ParsingError> {
+        let mut paths: BTreeSet<PathBuf> = self.files.iter().cloned().collect();
+        for directory in &self.directories {
+            self.add_directory_files(directory, directory, &mut paths)?;
+        }
+        if paths.is_empty() {
+            return Err(ParsingError::ErrorWhileBrowsingTheDirectoryEntries(
+                "No files found".to_string(),
+            ));
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Adds the selected files of the provided directory to the paths, browsing its
+    /// subdirectories if the selection is recursive.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The directory that was added to the selection.
+    /// * `directory` - The directory being browsed.
+    /// * `paths` - The paths found so far.
+    ///
+    /// # Returns
+    ///
+    /// A result that could carry a ParsingError.
+    fn add_directory_files(
+        &self,
+        root: &Path,
+        directory: &Path,
+        paths: &mut BTreeSet<PathBuf>,
+    ) -> Result<(), ParsingError> {
+        for entry in get_directory(directory)? {
+            let valid_entry = match entry {
+                Ok(valid_entry) => valid_entry,
+                Err(_) => continue,
+            };
+            let path = valid_entry.path();
+            let is_directory = match valid_entry.file_type() {
+                Ok(file_type) => file_type.is_dir(),
+                Err(_) => continue,
+            };
+            if is_directory {
+                if self.recursive {
+                    self.add_directory_files(root, &path, paths)?;
+                }
+            } else if Compression::from_path(&path).is_some() && self.is_selected(root, &path) {
+                paths.insert(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether a file found in a directory passes the include and exclude patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The directory that was added to the selection.
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// True if the file should be loaded.
+    fn is_selected(&self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let matches = |pattern: &Pattern| {
+            pattern.matches_path(relative)
+                || path
+                    .file_name()
+                    .is_some_and(|name| pattern.matches(&name.to_string_lossy()))
+        };
+        let included = self.include.is_empty() || self.include.iter().any(matches);
+        included && !self.exclude.iter().any(matches)
+    }
+}
+
+/// Returns the directory from the provided path. If the directory does not exist, it returns an error.
+///
+/// # Arguments
+///
+/// * `files_path` - The path where the files are located.
+///
+/// # Returns
+///
+/// A result that could carry a ReadDir or a ParsingError.
+fn get_directory(files_path: &Path) -> Result<ReadDir, ParsingError> {
+    match read_dir(files_path) {
+        Ok(directory) => Ok(directory),
+        Err(_) => Err(ParsingError::ErrorCouldNotOpenDataDirectory),
+    }
+}
+
+/// Compiles the provided glob pattern. If the pattern is invalid, it returns an error.
+///
+/// # Arguments
+///
+/// * `pattern` - The glob pattern.
+///
+/// # Returns
+///
+/// A result with the compiled pattern or a ParsingError.
+fn compile_pattern(pattern: &str) -> Result<Pattern, ParsingError> {
+    match Pattern::new(pattern) {
+        Ok(pattern) => Ok(pattern),
+        Err(error) => Err(ParsingError::ErrorInvalidPattern(
+            pattern.to_string(),
+            error.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TESTING_DATA: &str = "tests/testing_data";
+
+    fn file_names(paths: Vec<PathBuf>) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn directories_are_not_browsed_recursively_by_default() {
+        let selection = InputSelection::new().directory(TESTING_DATA);
+        assert!(matches!(
+            selection.files_paths(),
+            Err(ParsingError::ErrorWhileBrowsingTheDirectoryEntries(_))
+        ));
+    }
+
+    #[test]
+    fn recursive_selection_finds_nested_files() {
+        let selection = InputSelection::new()
+            .directory(TESTING_DATA)
+            .recursive(true);
+        let names = file_names(selection.files_paths().unwrap());
+        assert!(names.contains(&"30_preguntas.jsonl".to_string()));
+        assert!(names.contains(&"100_preguntas.jsonl".to_string()));
+        assert!(names.contains(&"30_preguntas.jsonl.gz".to_string()));
+    }
+
+    #[test]
+    fn patterns_filter_the_files() {
+        let selection = InputSelection::new()
+            .directory(TESTING_DATA)
+            .recursive(true)
+            .include("dos_archivos/*")
+            .unwrap()
+            .exclude("70_*")
+            .unwrap();
+        let names = file_names(selection.files_paths().unwrap());
+        assert_eq!(names, vec!["100_preguntas.jsonl"]);
+    }
+
+    #[test]
+    fn explicit_files_and_directories_are_combined_without_duplicates() {
+        let selection = InputSelection::new()
+            .directory(format!("{}/dos_archivos", TESTING_DATA))
+            .file(format!("{}/dos_archivos/70_preguntas.jsonl", TESTING_DATA))
+            .file(format!("{}/un_archivo/30_preguntas.jsonl", TESTING_DATA));
+        let names = file_names(selection.files_paths().unwrap());
+        assert_eq!(
+            names,
+            vec![
+                "100_preguntas.jsonl",
+                "70_preguntas.jsonl",
+                "30_preguntas.jsonl"
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_errors() {
+        assert!(matches!(
+            InputSelection::new().include("[a"),
+            Err(ParsingError::ErrorInvalidPattern(_, _))
+        ));
+    }
+
+    #[test]
+    fn missing_directories_are_errors() {
+        let selection = InputSelection::new().directory("this/directory/does/not/exist");
+        assert!(matches!(
+            selection.files_paths(),
+            Err(ParsingError::ErrorCouldNotOpenDataDirectory)
+        ));
+    }
+}
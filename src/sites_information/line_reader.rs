@@ -0,0 +1,626 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind, Seek, SeekFrom},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use rayon::prelude::*;
+
+use super::{
+    compression::{open_file, Compression, FileFormat},
+    diagnostics::{DiagnosticKind, FileDiagnostics, LineDiagnostic},
+    question_information::QuestionInformation,
+    question_parser::QuestionParser,
+    site::Site,
+    xml_reader::read_posts_xml,
+};
+
+/// Default size in bytes of the chunks in which plain files are split.
+pub const DEFAULT_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Enum that represents how the lines of a file are distributed among the threads.
+///
+/// # Variants
+///
+/// * `Chunked` - Plain files are split in byte ranges of the given size, aligned to the line breaks,
+///   and each range is parsed in parallel reusing a single buffer. Compressed files can not be
+///   split, so they are read line by line.
+/// * `LineBridge` - Every file is read line by line by a single iterator, allocating a string per
+///   line, and the lines are distributed among the threads with `par_bridge`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadStrategy {
+    Chunked(u64),
+    LineBridge,
+}
+
+impl Default for ReadStrategy {
+    /// Returns the chunked strategy with the default chunk size.
+    ///
+    /// # Returns
+    ///
+    /// The default read strategy.
+    fn default() -> Self {
+        ReadStrategy::Chunked(DEFAULT_CHUNK_SIZE)
+    }
+}
+
+/// Keeps the first part of a file where an invalid line was found when the file is read in strict
+/// mode, so the threads stop reading the parts that follow it. The parts before it are still read,
+/// so the invalid line with the lowest number is the one reported.
+///
+/// # Attributes
+///
+/// * `strict` - Whether the reading stops at the first invalid line.
+/// * `first_error` - The lowest part, a line or a chunk, with an invalid line found so far.
+pub(crate) struct StrictStop {
+    strict: bool,
+    first_error: AtomicU64,
+}
+
+impl StrictStop {
+    /// Creates the stop of a file, without errors found yet.
+    pub(crate) fn new(strict: bool) -> Self {
+        StrictStop {
+            strict,
+            first_error: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    /// Returns whether the part comes after a part with an invalid line, so it is not needed.
+    pub(crate) fn is_after(&self, part: u64) -> bool {
+        self.first_error.load(Ordering::Relaxed) < part
+    }
+
+    /// Records that an invalid line was found in the part.
+    ///
+    /// # Returns
+    ///
+    /// Whether the rest of the part should not be read.
+    pub(crate) fn record(&self, part: u64) -> bool {
+        if self.strict {
+            self.first_error.fetch_min(part, Ordering::Relaxed);
+        }
+        self.strict
+    }
+}
+
+/// Represents the partial result of reading a part of a file.
+///
+/// # Attributes
+///
+/// * `site` - The site with the questions of the part.
+/// * `diagnostics` - The problems found in the part, with line numbers relative to its first line.
+/// * `lines` - The amount of lines of the part.
+struct PartialRead {
+    site: Site,
+    diagnostics: FileDiagnostics,
+    lines: u64,
+}
+
+impl PartialRead {
+    /// Creates an empty partial result, with a site created by the parser.
+    fn new(site_name: &Option<String>, file_path: &str, parser: &QuestionParser) -> Self {
+        PartialRead {
+            site: parser.new_site(site_name.clone()),
+            diagnostics: FileDiagnostics::new(file_path.to_string()),
+            lines: 0,
+        }
+    }
+
+    /// Appends the partial result of the part that follows this one in the file.
+    fn append(&mut self, mut next: Self) {
+        next.diagnostics.offset_lines(self.lines);
+        self.site.merge(next.site);
+        self.diagnostics.merge(next.diagnostics);
+        self.lines += next.lines;
+    }
+}
+
+/// Reads the site stored in the provided file with the provided strategy.
+/// `Posts.xml` dumps are streamed by the XML reader regardless of the strategy.
+/// Lines that could not be used are skipped and reported in the returned diagnostics.
+/// In strict mode, the lines after the first invalid line are not read.
+/// If the file could not be opened, it returns an error.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `site_name` - The name of the site.
+/// * `strategy` - How the lines are distributed among the threads.
+/// * `strict` - Whether the reading stops at the first invalid line.
+/// * `parser` - The parser of the questions of the lines.
+///
+/// # Returns
+///
+/// A result with the site, the diagnostics and the amount of lines of the file,
+/// or the error that prevented opening it.
+pub fn read_site(
+    path: &Path,
+    site_name: Option<String>,
+    strategy: ReadStrategy,
+    strict: bool,
+    parser: &QuestionParser,
+) -> std::io::Result<(Site, FileDiagnostics, u64)> {
+    if FileFormat::from_path(path) == Some(FileFormat::PostsXml) {
+        return read_posts_xml(path, site_name, strict, parser);
+    }
+    let file_path = path.display().to_string();
+    let read = match (strategy, Compression::from_path(path)) {
+        (ReadStrategy::Chunked(chunk_size), Some(Compression::Plain)) => read_by_chunks(
+            path,
+            &site_name,
+            &file_path,
+            (0, chunk_size.max(1)),
+            strict,
+            parser,
+        )?,
+        _ => read_by_lines(path, &site_name, &file_path, strict, parser)?,
+    };
+    Ok((read.site, read.diagnostics, read.lines))
+}
+
+/// Reads the lines of a plain file that start at or after the provided offset, which must be
+/// the start of a line. It is used to read only the lines appended to a file already read.
+/// The line numbers of the diagnostics are relative to the first line after the offset.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `site_name` - The name of the site.
+/// * `offset` - The byte where the lines to read start.
+/// * `strategy` - The size of the ranges in which the lines are split, if it is chunked.
+/// * `strict` - Whether the reading stops at the first invalid line.
+/// * `parser` - The parser of the questions of the lines.
+///
+/// # Returns
+///
+/// A result with the site, the diagnostics and the amount of lines read,
+/// or the error that prevented opening the file.
+pub fn read_site_from(
+    path: &Path,
+    site_name: Option<String>,
+    offset: u64,
+    strategy: ReadStrategy,
+    strict: bool,
+    parser: &QuestionParser,
+) -> std::io::Result<(Site, FileDiagnostics, u64)> {
+    let file_path = path.display().to_string();
+    let chunk_size = match strategy {
+        ReadStrategy::Chunked(chunk_size) => chunk_size.max(1),
+        ReadStrategy::LineBridge => DEFAULT_CHUNK_SIZE,
+    };
+    let read = read_by_chunks(
+        path,
+        &site_name,
+        &file_path,
+        (offset, chunk_size),
+        strict,
+        parser,
+    )?;
+    Ok((read.site, read.diagnostics, read.lines))
+}
+
+/// Parses a line of a file into the information of a question.
+///
+/// # Arguments
+///
+/// * `line_number` - The number of the line, used in the diagnostic if it is invalid.
+/// * `line` - The bytes of the line, without the line break.
+/// * `site_name` - The name of the site of the line, used by the filter of the parser.
+/// * `parser` - The parser of the questions of the lines.
+///
+/// # Returns
+///
+/// A result with the information of the question, or None if the line does not hold a question,
+/// or the problem found in the line.
+fn parse_line(
+    line_number: u64,
+    line: &[u8],
+    site_name: &Option<String>,
+    parser: &QuestionParser,
+) -> Result<Option<QuestionInformation>, LineDiagnostic> {
+    let valid_line = match std::str::from_utf8(line) {
+        Ok(valid_line) => valid_line,
+        Err(_) => {
+            return Err(LineDiagnostic::new(
+                line_number,
+                DiagnosticKind::UnreadableLine,
+                "stream did not contain valid UTF-8".to_string(),
+            ))
+        }
+    };
+    match parser.parse(valid_line, site_name.as_deref()) {
+        Ok(question) => Ok(question),
+        Err(error) => Err(LineDiagnostic::new(
+            line_number,
+            DiagnosticKind::MalformedLine,
+            error.to_string(),
+        )),
+    }
+}
+
+/// Adds the result of parsing a line to a partial result.
+fn add_line(
+    mut read: PartialRead,
+    line: Result<Option<QuestionInformation>, LineDiagnostic>,
+) -> PartialRead {
+    match line {
+        Ok(Some(question)) => read.site.add_question(question),
+        Ok(None) => {}
+        Err(error) => read.diagnostics.add_line_error(error),
+    }
+    read
+}
+
+/// Reads the file line by line with a single iterator, distributing the lines among the threads.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `site_name` - The name of the site.
+/// * `file_path` - The path of the file used in the diagnostics.
+/// * `strict` - Whether the reading stops at the first invalid line.
+/// * `parser` - The parser of the questions of the lines.
+///
+/// # Returns
+///
+/// A result with the partial result of the whole file.
+fn read_by_lines(
+    path: &Path,
+    site_name: &Option<String>,
+    file_path: &str,
+    strict: bool,
+    parser: &QuestionParser,
+) -> std::io::Result<PartialRead> {
+    let reader = open_file(path)?;
+    let stop = StrictStop::new(strict);
+    let read = reader
+        .lines()
+        .enumerate()
+        .scan(false, |stopped, (index, line)| {
+            // A read error other than invalid UTF-8 means the rest of the file can not be read.
+            let line_number = index as u64 + 1;
+            if *stopped || stop.is_after(line_number) {
+                return None;
+            }
+            if let Err(error) = &line {
+                *stopped = error.kind() != ErrorKind::InvalidData;
+            }
+            Some((line_number, line))
+        })
+        .par_bridge()
+        .fold(
+            || PartialRead::new(site_name, file_path, parser),
+            |mut read, (line_number, line)| {
+                // The lines already handed to the threads when an invalid line is found are skipped.
+                if stop.is_after(line_number) {
+                    return read;
+                }
+                let line = match line {
+                    Ok(line) => parse_line(line_number, line.as_bytes(), site_name, parser),
+                    Err(error) => Err(LineDiagnostic::new(
+                        line_number,
+                        DiagnosticKind::UnreadableLine,
+                        error.to_string(),
+                    )),
+                };
+                if line.is_err() {
+                    stop.record(line_number);
+                }
+                read.lines += 1;
+                add_line(read, line)
+            },
+        )
+        .reduce(
+            || PartialRead::new(site_name, file_path, parser),
+            |mut read, other| {
+                // The line numbers are absolute, so the parts are merged without offsets.
+                read.site.merge(other.site);
+                read.diagnostics.merge(other.diagnostics);
+                read.lines += other.lines;
+                read
+            },
+        );
+    Ok(read)
+}
+
+/// Reads a plain file from the provided offset splitting it in byte ranges that are parsed in parallel.
+/// Each range holds the lines that start inside it, so the ranges never share a line.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `site_name` - The name of the site.
+/// * `file_path` - The path of the file used in the diagnostics.
+/// * `(offset, chunk_size)` - The byte where the first line starts and the size in bytes of the ranges.
+/// * `strict` - Whether the reading stops at the first invalid line.
+/// * `parser` - The parser of the questions of the lines.
+///
+/// # Returns
+///
+/// A result with the partial result of the whole file.
+fn read_by_chunks(
+    path: &Path,
+    site_name: &Option<String>,
+    file_path: &str,
+    (offset, chunk_size): (u64, u64),
+    strict: bool,
+    parser: &QuestionParser,
+) -> std::io::Result<PartialRead> {
+    let file_size = File::open(path)?.metadata()?.len();
+    let chunks = file_size.saturating_sub(offset).div_ceil(chunk_size);
+    let stop = StrictStop::new(strict);
+
+    let read = (0..chunks)
+        .into_par_iter()
+        .map(|chunk| {
+            if stop.is_after(chunk) {
+                return PartialRead::new(site_name, file_path, parser);
+            }
+            let start = offset.saturating_add(chunk.saturating_mul(chunk_size));
+            let end = start.saturating_add(chunk_size).min(file_size);
+            read_chunk(
+                path,
+                site_name,
+                file_path,
+                (start, end),
+                (&stop, chunk),
+                parser,
+            )
+        })
+        .reduce(
+            || PartialRead::new(site_name, file_path, parser),
+            |mut read, next| {
+                read.append(next);
+                read
+            },
+        );
+    Ok(read)
+}
+
+/// Reads the lines that start in the provided byte range of a file, reusing a single buffer.
+/// The line numbers of the diagnostics are relative to the first line of the range.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `site_name` - The name of the site.
+/// * `file_path` - The path of the file used in the diagnostics.
+/// * `(start, end)` - The first byte of the range and the byte after its last byte.
+/// * `(stop, chunk)` - The stop shared by the ranges of the file and the index of the range, used
+///   to stop reading it once an invalid line is found in it or in a previous range.
+/// * `parser` - The parser of the questions of the lines.
+///
+/// # Returns
+///
+/// The partial result of the range.
+fn read_chunk(
+    path: &Path,
+    site_name: &Option<String>,
+    file_path: &str,
+    (start, end): (u64, u64),
+    (stop, chunk): (&StrictStop, u64),
+    parser: &QuestionParser,
+) -> PartialRead {
+    let mut read = PartialRead::new(site_name, file_path, parser);
+    let mut reader = match File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(error) => {
+            read.diagnostics.add_line_error(LineDiagnostic::new(
+                1,
+                DiagnosticKind::UnreadableLine,
+                error.to_string(),
+            ));
+            stop.record(chunk);
+            return read;
+        }
+    };
+    let mut buffer = Vec::new();
+    let mut position = start;
+
+    // The line that crosses the start of the range belongs to the previous range.
+    if start > 0 {
+        position -= 1;
+        if let Err(error) = reader
+            .seek(SeekFrom::Start(position))
+            .and_then(|_| reader.read_until(b'\n', &mut buffer))
+        {
+            read.diagnostics.add_line_error(LineDiagnostic::new(
+                1,
+                DiagnosticKind::UnreadableLine,
+                error.to_string(),
+            ));
+            stop.record(chunk);
+            return read;
+        }
+        position += buffer.len() as u64;
+    }
+
+    while position < end && !stop.is_after(chunk) {
+        buffer.clear();
+        let line_number = read.lines + 1;
+        match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(bytes) => {
+                position += bytes as u64;
+                read.lines += 1;
+                let line = parse_line(line_number, trim_line_break(&buffer), site_name, parser);
+                let invalid = line.is_err();
+                read = add_line(read, line);
+                if invalid && stop.record(chunk) {
+                    break;
+                }
+            }
+            Err(error) => {
+                read.lines += 1;
+                read.diagnostics.add_line_error(LineDiagnostic::new(
+                    line_number,
+                    DiagnosticKind::UnreadableLine,
+                    error.to_string(),
+                ));
+                stop.record(chunk);
+                break;
+            }
+        }
+    }
+    read
+}
+
+/// Removes the line break at the end of a line, if it has one.
+fn trim_line_break(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites_information::ranking::Ranking;
+
+    const FILE_WITH_ERRORS: &str = "tests/testing_data/con_errores/5_preguntas_3_errores.jsonl";
+    const FILE_WITHOUT_ERRORS: &str = "tests/testing_data/dos_archivos/70_preguntas.jsonl";
+
+    fn error_lines(diagnostics: &FileDiagnostics) -> Vec<(u64, DiagnosticKind)> {
+        diagnostics
+            .errors
+            .iter()
+            .map(|error| (error.line, error.kind))
+            .collect()
+    }
+
+    #[test]
+    fn chunks_and_lines_give_the_same_site() {
+        let path = Path::new(FILE_WITHOUT_ERRORS);
+        let (by_lines, _, lines) = read_site(
+            path,
+            None,
+            ReadStrategy::LineBridge,
+            false,
+            &QuestionParser::default(),
+        )
+        .unwrap();
+        let by_lines = by_lines.generate_report(&Ranking::chatty(0));
+
+        for chunk_size in [1, 7, 100, 4096, DEFAULT_CHUNK_SIZE] {
+            let (by_chunks, diagnostics, chunk_lines) = read_site(
+                path,
+                None,
+                ReadStrategy::Chunked(chunk_size),
+                false,
+                &QuestionParser::default(),
+            )
+            .unwrap();
+            assert_eq!(by_chunks.generate_report(&Ranking::chatty(0)), by_lines);
+            assert!(!diagnostics.has_problems());
+            assert_eq!(chunk_lines, lines);
+        }
+    }
+
+    #[test]
+    fn reading_from_an_offset_only_reads_the_following_lines() {
+        let path = Path::new(FILE_WITH_ERRORS);
+        let contents = std::fs::read(path).unwrap();
+        let second_line = contents.iter().position(|byte| *byte == b'\n').unwrap() as u64 + 1;
+        let parser = QuestionParser::default();
+
+        let (whole, whole_diagnostics, whole_lines) =
+            read_site(path, None, ReadStrategy::LineBridge, false, &parser).unwrap();
+        for strategy in [ReadStrategy::LineBridge, ReadStrategy::Chunked(13)] {
+            let (mut first, _, first_lines) =
+                read_site_from(path, None, 0, strategy, false, &parser).unwrap();
+            let (rest, mut diagnostics, rest_lines) =
+                read_site_from(path, None, second_line, strategy, false, &parser).unwrap();
+            diagnostics.offset_lines(1);
+
+            assert_eq!(first_lines, whole_lines);
+            assert_eq!(rest_lines, whole_lines - 1);
+            assert_eq!(error_lines(&diagnostics), error_lines(&whole_diagnostics));
+            assert_eq!(
+                first.generate_report(&Ranking::chatty(0)),
+                whole.generate_report(&Ranking::chatty(0))
+            );
+            first.merge(rest);
+            assert_eq!(
+                first.generate_report(&Ranking::chatty(0)).questions,
+                2 * whole.generate_report(&Ranking::chatty(0)).questions - 1
+            );
+        }
+    }
+
+    #[test]
+    fn chunks_report_absolute_line_numbers() {
+        let path = Path::new(FILE_WITH_ERRORS);
+        let (_, by_lines, _) = read_site(
+            path,
+            None,
+            ReadStrategy::LineBridge,
+            false,
+            &QuestionParser::default(),
+        )
+        .unwrap();
+
+        for chunk_size in [1, 13, 250, DEFAULT_CHUNK_SIZE] {
+            let (_, by_chunks, _) = read_site(
+                path,
+                None,
+                ReadStrategy::Chunked(chunk_size),
+                false,
+                &QuestionParser::default(),
+            )
+            .unwrap();
+            assert_eq!(error_lines(&by_chunks), error_lines(&by_lines));
+            assert_eq!(by_chunks.skipped_lines, 3);
+        }
+    }
+
+    #[test]
+    fn strict_reading_stops_at_the_first_invalid_line() {
+        let path = std::env::temp_dir().join(format!("tp1_strict_{}.jsonl", std::process::id()));
+        let mut contents = "not a question\n".to_string();
+        for _ in 0..10_000 {
+            contents.push_str("{\"texts\": [\"a question\"], \"tags\": [\"rust\"]}\n");
+        }
+        contents.push_str("another invalid line\n");
+        std::fs::write(&path, contents).unwrap();
+
+        for strategy in [ReadStrategy::LineBridge, ReadStrategy::Chunked(64)] {
+            let (_, diagnostics, _) =
+                read_site(&path, None, strategy, true, &QuestionParser::default()).unwrap();
+            assert_eq!(diagnostics.errors[0].line, 1);
+        }
+        // A single range is read by a single thread, which stops right after the invalid line.
+        let (_, diagnostics, lines) = read_site(
+            &path,
+            None,
+            ReadStrategy::Chunked(DEFAULT_CHUNK_SIZE),
+            true,
+            &QuestionParser::default(),
+        )
+        .unwrap();
+        assert_eq!((lines, diagnostics.skipped_lines), (1, 1));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn strict_reading_reports_the_lowest_invalid_line() {
+        let path = Path::new(FILE_WITH_ERRORS);
+        let parser = QuestionParser::default();
+        let (_, all, _) = read_site(path, None, ReadStrategy::LineBridge, false, &parser).unwrap();
+        for strategy in [
+            ReadStrategy::LineBridge,
+            ReadStrategy::Chunked(1),
+            ReadStrategy::Chunked(13),
+        ] {
+            let (_, strict, _) = read_site(path, None, strategy, true, &parser).unwrap();
+            assert_eq!(strict.errors[0].line, all.errors[0].line);
+        }
+    }
+
+    #[test]
+    fn line_breaks_are_trimmed() {
+        assert_eq!(trim_line_break(b"line\r\n"), b"line");
+        assert_eq!(trim_line_break(b"line\n"), b"line");
+        assert_eq!(trim_line_break(b"line"), b"line");
+    }
+}
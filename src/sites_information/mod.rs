@@ -1,21 +1,75 @@
-/// This module contains all the structs that are used to store the information about the sites and the questions.
+//! This module contains all the structs that are used to store the information about the sites and the questions.
+
+/// Contains the persisted state of an analysis, used to only read the files that changed.
+pub mod analysis_state;
 
 /// Represents the full question.
 pub mod question;
 /// Represents the information of a question.
 pub mod question_information;
 
+/// Contains the schemas of the JSON questions that can be read.
+pub mod input_schema;
+
+/// Contains the filter expressions that select the questions that are aggregated.
+pub mod question_filter;
+
+/// Contains the parser of the lines of the files into questions.
+pub mod question_parser;
+
 /// Represents the information of a site.
 pub mod site;
 
+/// Contains the canonical names of the sites derived from the paths of their files.
+pub mod site_id;
+
+/// Contains the rules that assign the files to the sites they belong to.
+pub mod site_grouping;
+
+/// Contains the matrix of the pairs of tags that appear together in the questions.
+pub mod co_occurrence_matrix;
+
+/// Contains the readers that decompress the files with questions.
+pub mod compression;
+
+/// Contains the report of the problems found while loading the sites.
+pub mod diagnostics;
+
+/// Contains the exporters of the report as CSV and TSV tables.
+pub mod export;
+
+/// Contains the selection of the files that are loaded.
+pub mod input_selection;
+
+/// Contains the parallel readers of the lines of a file.
+pub mod line_reader;
+
+/// Contains the streaming reader of the posts of the official StackExchange XML dumps.
+pub mod xml_reader;
+
+/// Contains the rankings of sites and tags by different metrics.
+pub mod ranking;
+
+/// Contains the typed report generated from a collection of sites.
+pub mod report;
+
 /// Represents a collection of sites with their information.
 pub mod sites_collection;
 
+/// Contains the layout of the texts of the questions.
+pub mod text_layout;
+
+/// Contains the tokenizers that count the words of the texts.
+pub mod tokenizer;
+
 /// Represents the information of a tag.
 pub mod tag_information;
 
 /// Represents a collection of tags with their information.
 pub mod tags_collection;
 
+/// Contains the mergeable distribution of the amount of words of the questions.
+pub mod word_distribution;
+
 /// Contains the error that can occur when parsing the sites information.
 pub mod parsing_error;
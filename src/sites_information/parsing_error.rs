@@ -1,3 +1,5 @@
+use std::fmt;
+
 /// Enum that represents the possible errors that can occur while parsing the data.
 ///
 /// # Variants
@@ -5,9 +7,70 @@
 /// * `ErrorCouldNotOpenDataDirectory` - The program could not open the data directory.
 /// * `ErrorWhileBrowsingTheDirectoryEntries` - An error occurred while browsing the directory entries.
 /// * `ErrorWhileBuildingThreadpool` - An error occurred while building the threadpool.
+/// * `ErrorCouldNotReadFile` - A file could not be read. Carries the path of the file and the error.
+/// * `ErrorInvalidLine` - A line could not be parsed. Carries the path of the file, the line number and the error.
+/// * `ErrorInvalidPattern` - A pattern to select or group files is invalid. Carries the pattern and the error.
+/// * `ErrorInvalidState` - A state file could not be read or it is invalid. Carries the path of the file and the error.
+/// * `ErrorCouldNotWriteState` - A state file could not be written. Carries the path of the file and the error.
+/// * `ErrorInvalidSiteMapping` - A file that assigns files to sites could not be read or it is invalid. Carries the path of the file and the error.
+/// * `ErrorDuplicateSite` - Two files belong to the same site and duplicates are not merged. Carries the name of the site and the paths of both files.
+/// * `ErrorInvalidFilter` - A filter expression of the questions is invalid. Carries the expression and the error.
 #[derive(Debug)]
 pub enum ParsingError {
     ErrorCouldNotOpenDataDirectory,
     ErrorWhileBrowsingTheDirectoryEntries(String),
     ErrorWhileBuildingThreadpool(String),
+    ErrorCouldNotReadFile(String, String),
+    ErrorInvalidLine(String, u64, String),
+    ErrorInvalidPattern(String, String),
+    ErrorInvalidState(String, String),
+    ErrorCouldNotWriteState(String, String),
+    ErrorInvalidSiteMapping(String, String),
+    ErrorDuplicateSite(String, String, String),
+    ErrorInvalidFilter(String, String),
+}
+
+impl fmt::Display for ParsingError {
+    /// Formats the error as a message that can be shown to the user.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsingError::ErrorCouldNotOpenDataDirectory => {
+                write!(f, "Could not open the data directory")
+            }
+            ParsingError::ErrorWhileBrowsingTheDirectoryEntries(error) => {
+                write!(f, "Error while browsing the directory entries: {}", error)
+            }
+            ParsingError::ErrorWhileBuildingThreadpool(error) => {
+                write!(f, "Error while building the threadpool: {}", error)
+            }
+            ParsingError::ErrorCouldNotReadFile(path, error) => {
+                write!(f, "Could not read the file '{}': {}", path, error)
+            }
+            ParsingError::ErrorInvalidLine(path, line, error) => {
+                write!(f, "Invalid line {} of the file '{}': {}", line, path, error)
+            }
+            ParsingError::ErrorInvalidPattern(pattern, error) => {
+                write!(f, "Invalid pattern '{}': {}", pattern, error)
+            }
+            ParsingError::ErrorInvalidState(path, error) => {
+                write!(f, "Invalid state file '{}': {}", path, error)
+            }
+            ParsingError::ErrorCouldNotWriteState(path, error) => {
+                write!(f, "Could not write the state file '{}': {}", path, error)
+            }
+            ParsingError::ErrorInvalidSiteMapping(path, error) => {
+                write!(f, "Invalid site mapping file '{}': {}", path, error)
+            }
+            ParsingError::ErrorDuplicateSite(site, first, second) => {
+                write!(
+                    f,
+                    "The files '{}' and '{}' belong to the same site '{}'",
+                    first, second, site
+                )
+            }
+            ParsingError::ErrorInvalidFilter(expression, error) => {
+                write!(f, "Invalid filter '{}': {}", expression, error)
+            }
+        }
+    }
 }
@@ -1,6 +1,10 @@
 use serde::Deserialize;
 
-use super::question_information::QuestionInformation;
+use super::{
+    question_information::{QuestionInformation, QuestionTexts},
+    text_layout::TextLayout,
+    tokenizer::Tokenizer,
+};
 
 /// Question struct.
 ///
@@ -15,20 +19,53 @@ pub struct Question {
 }
 
 impl Question {
+    /// Converts the question into its texts, with the field held by each one.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The field held by each of the texts of the question.
+    ///
+    /// # Returns
+    ///
+    /// The texts and the tags of the question.
+    pub fn into_texts(self, layout: &TextLayout) -> QuestionTexts {
+        let texts = self
+            .texts
+            .into_iter()
+            .enumerate()
+            .map(|(position, text)| (layout.field_at(position), text))
+            .collect();
+        QuestionTexts {
+            texts,
+            tags: self.tags,
+        }
+    }
+
     /// Converts the question into a QuestionInformation instance.
     ///
+    /// # Arguments
+    ///
+    /// * `layout` - The field held by each of the texts of the question.
+    /// * `tokenizer` - The tokenizer that counts the words of the texts.
+    ///
     /// # Returns
     ///
     /// A QuestionInformation instance with the information of the question.
-    pub fn into_information(self) -> QuestionInformation {
-        let word_count: usize = self.texts.iter().map(|text| text.split_whitespace().count()).sum();
-        QuestionInformation::new(word_count as u16, self.tags)
+    pub fn into_information(
+        self,
+        layout: &TextLayout,
+        tokenizer: &dyn Tokenizer,
+    ) -> QuestionInformation {
+        self.into_texts(layout).into_information(tokenizer)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sites_information::{
+        question_information::FieldWords, tokenizer::WhitespaceTokenizer,
+    };
 
     #[test]
     fn into_information() {
@@ -36,11 +73,45 @@ mod tests {
             texts: vec!["Hello".to_string(), "World".to_string()],
             tags: vec!["tag1".to_string(), "tag2".to_string()],
         };
-        let question_information = question.into_information();
-        assert_eq!(question_information.words, 10);
+        let question_information =
+            question.into_information(&TextLayout::default(), &WhitespaceTokenizer);
+        assert_eq!(question_information.words, 2);
         assert_eq!(
             question_information.tags,
             vec!["tag1".to_string(), "tag2".to_string()]
         );
     }
+
+    #[test]
+    fn long_texts_are_counted_exactly() {
+        let words = u16::MAX as usize + 10;
+        let question = Question {
+            texts: vec!["word ".repeat(words), "title".to_string()],
+            tags: vec!["tag1".to_string()],
+        };
+        let question_information =
+            question.into_information(&TextLayout::default(), &WhitespaceTokenizer);
+        assert_eq!(question_information.words, words as u64 + 1);
+        assert_eq!(question_information.fields.title, words as u64);
+        assert_eq!(question_information.fields.body, 1);
+    }
+
+    #[test]
+    fn texts_are_counted_by_their_field() {
+        let question = Question {
+            texts: vec![
+                "The title".to_string(),
+                "A longer body".to_string(),
+                "An answer".to_string(),
+            ],
+            tags: vec!["tag1".to_string()],
+        };
+        let layout = TextLayout::from_spec("title,body,other").unwrap();
+        let question_information = question.into_information(&layout, &WhitespaceTokenizer);
+        assert_eq!(question_information.words, 7);
+        assert_eq!(
+            question_information.fields,
+            FieldWords { title: 2, body: 3 }
+        );
+    }
 }
@@ -0,0 +1,631 @@
+use std::{fmt, iter::Peekable, str::Chars, vec::IntoIter};
+
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+
+use super::{
+    parsing_error::ParsingError, question_information::QuestionInformation, text_layout::TextField,
+};
+
+/// Options used to match the names of the tags and of the sites, ignoring the case.
+const NAME_MATCHING: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+/// Operators that compare the amount of words, with the longest ones first.
+const OPERATORS: [&str; 5] = ["<=", ">=", "<", ">", "="];
+
+/// Represents a filter of the questions that are aggregated, parsed from an expression such as
+/// `tag:rust and not (site:stackoverflow.com or words<10)`.
+///
+/// The predicates are combined with `and`, `or`, `not` and parentheses. `not` binds tighter than
+/// `and`, and `and` binds tighter than `or`. Predicates written one after the other are joined with
+/// `and`. The predicates are:
+///
+/// * `tag:<glob>` - The question has a tag that matches the glob pattern, such as `python*`.
+/// * `site:<glob>` - The question belongs to a site whose name matches the glob pattern.
+/// * `words<op><n>`, `title_words<op><n>` and `body_words<op><n>` - The amount of words of the
+///   question, of its title or of its body compared with `<`, `<=`, `=`, `>=` or `>`.
+/// * `text:<regex>`, `title:<regex>` and `body:<regex>` - Any text of the question, its title or its
+///   body matches the regular expression.
+///
+/// Values with spaces or parentheses are written between double quotes, such as
+/// `text:"(?i)dead lock"`. Inside the quotes, `\"` stands for a quote and any other backslash is kept.
+///
+/// # Attributes
+///
+/// * `expression` - The expression the filter was parsed from.
+/// * `condition` - The condition the questions must meet.
+#[derive(Debug, Clone)]
+pub struct QuestionFilter {
+    expression: String,
+    condition: Condition,
+}
+
+impl QuestionFilter {
+    /// Parses a filter expression. If the expression is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The filter expression.
+    ///
+    /// # Returns
+    ///
+    /// A result with the filter or a ParsingError.
+    pub fn parse(expression: &str) -> Result<Self, ParsingError> {
+        let invalid =
+            |error: String| ParsingError::ErrorInvalidFilter(expression.to_string(), error);
+        let mut tokens = tokenize(expression)
+            .map_err(invalid)?
+            .into_iter()
+            .peekable();
+        let condition = parse_or(&mut tokens).map_err(invalid)?;
+        if let Some(token) = tokens.next() {
+            return Err(invalid(format!("unexpected {}", token)));
+        }
+        Ok(QuestionFilter {
+            expression: expression.to_string(),
+            condition,
+        })
+    }
+
+    /// Returns the expression the filter was parsed from.
+    ///
+    /// # Returns
+    ///
+    /// The filter expression.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns whether the filter depends on the site of the questions.
+    ///
+    /// # Returns
+    ///
+    /// True if the expression has a `site` predicate.
+    pub fn uses_sites(&self) -> bool {
+        self.condition.uses_sites()
+    }
+
+    /// Returns whether no question of the provided site can pass the filter, because its `site`
+    /// predicates already reject the site, regardless of the rest of the expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The name of the site, if it has one.
+    ///
+    /// # Returns
+    ///
+    /// True if the questions of the site never have to be read.
+    pub fn excludes_site(&self, site: Option<&str>) -> bool {
+        self.condition.matches_site(site) == Some(false)
+    }
+
+    /// Returns whether a question passes the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The name of the site of the question, if it has one.
+    /// * `texts` - The texts of the question, with the field held by each one.
+    /// * `question` - The information of the question.
+    ///
+    /// # Returns
+    ///
+    /// True if the question should be aggregated.
+    pub fn matches(
+        &self,
+        site: Option<&str>,
+        texts: &[(TextField, String)],
+        question: &QuestionInformation,
+    ) -> bool {
+        self.condition.matches(site, texts, question)
+    }
+}
+
+/// Enum that represents a condition on the questions.
+///
+/// # Variants
+///
+/// * `Tag` - The question has a tag that matches the pattern.
+/// * `Site` - The site of the question matches the pattern.
+/// * `Words` - The amount of words of the field, or of the whole question, meets the comparison.
+/// * `Text` - A text of the field, or any text, matches the regular expression.
+/// * `Not` - The condition is not met.
+/// * `And` - Every condition is met.
+/// * `Or` - Any of the conditions is met.
+#[derive(Debug, Clone)]
+enum Condition {
+    Tag(Pattern),
+    Site(Pattern),
+    Words(Option<TextField>, Comparison, u64),
+    Text(Option<TextField>, Regex),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+impl Condition {
+    /// Returns whether the condition has a `Site` condition.
+    fn uses_sites(&self) -> bool {
+        match self {
+            Condition::Site(_) => true,
+            Condition::Not(condition) => condition.uses_sites(),
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                conditions.iter().any(Condition::uses_sites)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether every question of the site meets the condition, using only the `Site`
+    /// conditions. It returns None if it depends on the questions.
+    fn matches_site(&self, site: Option<&str>) -> Option<bool> {
+        match self {
+            Condition::Site(pattern) => {
+                Some(site.is_some_and(|site| pattern.matches_with(site, NAME_MATCHING)))
+            }
+            Condition::Not(condition) => condition.matches_site(site).map(|matches| !matches),
+            Condition::And(conditions) => {
+                let verdicts: Vec<_> = conditions
+                    .iter()
+                    .map(|condition| condition.matches_site(site))
+                    .collect();
+                if verdicts.contains(&Some(false)) {
+                    Some(false)
+                } else if verdicts.iter().all(|verdict| *verdict == Some(true)) {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            Condition::Or(conditions) => {
+                let verdicts: Vec<_> = conditions
+                    .iter()
+                    .map(|condition| condition.matches_site(site))
+                    .collect();
+                if verdicts.contains(&Some(true)) {
+                    Some(true)
+                } else if verdicts.iter().all(|verdict| *verdict == Some(false)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether a question meets the condition.
+    fn matches(
+        &self,
+        site: Option<&str>,
+        texts: &[(TextField, String)],
+        question: &QuestionInformation,
+    ) -> bool {
+        match self {
+            Condition::Tag(pattern) => question
+                .tags
+                .iter()
+                .any(|tag| pattern.matches_with(tag, NAME_MATCHING)),
+            Condition::Site(pattern) => {
+                site.is_some_and(|site| pattern.matches_with(site, NAME_MATCHING))
+            }
+            Condition::Words(field, comparison, amount) => {
+                let words = match field {
+                    Some(TextField::Title) => question.fields.title,
+                    Some(TextField::Body) => question.fields.body,
+                    _ => question.words,
+                };
+                comparison.holds(words, *amount)
+            }
+            Condition::Text(field, regex) => texts
+                .iter()
+                .filter(|(text_field, _)| field.is_none_or(|field| field == *text_field))
+                .any(|(_, text)| regex.is_match(text)),
+            Condition::Not(condition) => !condition.matches(site, texts, question),
+            Condition::And(conditions) => conditions
+                .iter()
+                .all(|condition| condition.matches(site, texts, question)),
+            Condition::Or(conditions) => conditions
+                .iter()
+                .any(|condition| condition.matches(site, texts, question)),
+        }
+    }
+}
+
+/// Enum that represents how an amount of words is compared with a bound.
+///
+/// # Variants
+///
+/// * `Less` - The amount is less than the bound.
+/// * `LessOrEqual` - The amount is less than or equal to the bound.
+/// * `Equal` - The amount is the bound.
+/// * `GreaterOrEqual` - The amount is greater than or equal to the bound.
+/// * `Greater` - The amount is greater than the bound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Returns the comparison written with the provided operator, if it is known.
+    fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            "<" => Some(Comparison::Less),
+            "<=" => Some(Comparison::LessOrEqual),
+            "=" => Some(Comparison::Equal),
+            ">=" => Some(Comparison::GreaterOrEqual),
+            ">" => Some(Comparison::Greater),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the amount meets the comparison with the bound.
+    fn holds(&self, amount: u64, bound: u64) -> bool {
+        match self {
+            Comparison::Less => amount < bound,
+            Comparison::LessOrEqual => amount <= bound,
+            Comparison::Equal => amount == bound,
+            Comparison::GreaterOrEqual => amount >= bound,
+            Comparison::Greater => amount > bound,
+        }
+    }
+}
+
+/// Enum that represents the tokens of a filter expression.
+///
+/// # Variants
+///
+/// * `Open` - An opening parenthesis.
+/// * `Close` - A closing parenthesis.
+/// * `And` - The `and` operator.
+/// * `Or` - The `or` operator.
+/// * `Not` - The `not` operator.
+/// * `Predicate` - A predicate, with the quotes of its value removed.
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Predicate(String),
+}
+
+impl fmt::Display for Token {
+    /// Writes the token as it appears in the expression.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Open => write!(formatter, "`(`"),
+            Token::Close => write!(formatter, "`)`"),
+            Token::And => write!(formatter, "`and`"),
+            Token::Or => write!(formatter, "`or`"),
+            Token::Not => write!(formatter, "`not`"),
+            Token::Predicate(predicate) => write!(formatter, "`{}`", predicate),
+        }
+    }
+}
+
+/// Splits a filter expression into its tokens.
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut characters = expression.chars().peekable();
+    while let Some(&character) = characters.peek() {
+        match character {
+            '(' => {
+                characters.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                characters.next();
+                tokens.push(Token::Close);
+            }
+            _ if character.is_whitespace() => {
+                characters.next();
+            }
+            _ => tokens.push(read_word(&mut characters)?),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads an operator or a predicate, which ends at a whitespace or a parenthesis outside quotes.
+fn read_word(characters: &mut Peekable<Chars>) -> Result<Token, String> {
+    let mut word = String::new();
+    let mut quoted = false;
+    while let Some(&character) = characters.peek() {
+        if character.is_whitespace() || character == '(' || character == ')' {
+            break;
+        }
+        characters.next();
+        if character == '"' {
+            quoted = true;
+            read_quoted(characters, &mut word)?;
+        } else {
+            word.push(character);
+        }
+    }
+    if quoted {
+        return Ok(Token::Predicate(word));
+    }
+    let token = match word.to_lowercase().as_str() {
+        "and" => Token::And,
+        "or" => Token::Or,
+        "not" => Token::Not,
+        _ => Token::Predicate(word),
+    };
+    Ok(token)
+}
+
+/// Reads the characters up to the closing quote into the word.
+fn read_quoted(characters: &mut Peekable<Chars>, word: &mut String) -> Result<(), String> {
+    while let Some(character) = characters.next() {
+        match character {
+            '"' => return Ok(()),
+            '\\' if characters.peek() == Some(&'"') => {
+                characters.next();
+                word.push('"');
+            }
+            _ => word.push(character),
+        }
+    }
+    Err("missing closing quote".to_string())
+}
+
+/// Parses conditions joined with `or`.
+fn parse_or(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Condition, String> {
+    let mut conditions = vec![parse_and(tokens)?];
+    while tokens.next_if_eq(&Token::Or).is_some() {
+        conditions.push(parse_and(tokens)?);
+    }
+    Ok(combine(conditions, Condition::Or))
+}
+
+/// Parses conditions joined with `and`, or written one after the other.
+fn parse_and(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Condition, String> {
+    let mut conditions = vec![parse_not(tokens)?];
+    loop {
+        let joined = tokens.next_if_eq(&Token::And).is_some()
+            || matches!(
+                tokens.peek(),
+                Some(Token::Not | Token::Open | Token::Predicate(_))
+            );
+        if !joined {
+            break;
+        }
+        conditions.push(parse_not(tokens)?);
+    }
+    Ok(combine(conditions, Condition::And))
+}
+
+/// Parses a negated condition, a condition between parentheses or a predicate.
+fn parse_not(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Condition, String> {
+    match tokens.next() {
+        Some(Token::Not) => Ok(Condition::Not(Box::new(parse_not(tokens)?))),
+        Some(Token::Open) => {
+            let condition = parse_or(tokens)?;
+            match tokens.next() {
+                Some(Token::Close) => Ok(condition),
+                Some(token) => Err(format!("expected `)` but found {}", token)),
+                None => Err("missing closing parenthesis".to_string()),
+            }
+        }
+        Some(Token::Predicate(predicate)) => parse_predicate(&predicate),
+        Some(token) => Err(format!("expected a predicate but found {}", token)),
+        None => Err("expected a predicate".to_string()),
+    }
+}
+
+/// Returns the only condition, or the conditions combined if there is more than one.
+fn combine(
+    mut conditions: Vec<Condition>,
+    combinator: fn(Vec<Condition>) -> Condition,
+) -> Condition {
+    match conditions.len() {
+        1 => conditions.remove(0),
+        _ => combinator(conditions),
+    }
+}
+
+/// Parses a predicate into its condition.
+fn parse_predicate(predicate: &str) -> Result<Condition, String> {
+    let key_length = predicate
+        .find(|character: char| !(character.is_ascii_alphanumeric() || character == '_'))
+        .unwrap_or(predicate.len());
+    let (key, rest) = predicate.split_at(key_length);
+    match key.to_lowercase().as_str() {
+        "tag" => Ok(Condition::Tag(parse_pattern(value_of(key, rest)?)?)),
+        "site" => Ok(Condition::Site(parse_pattern(value_of(key, rest)?)?)),
+        "text" => Ok(Condition::Text(None, parse_regex(value_of(key, rest)?)?)),
+        "title" => Ok(Condition::Text(
+            Some(TextField::Title),
+            parse_regex(value_of(key, rest)?)?,
+        )),
+        "body" => Ok(Condition::Text(
+            Some(TextField::Body),
+            parse_regex(value_of(key, rest)?)?,
+        )),
+        "words" => parse_words(None, key, rest),
+        "title_words" => parse_words(Some(TextField::Title), key, rest),
+        "body_words" => parse_words(Some(TextField::Body), key, rest),
+        _ => Err(format!("unknown predicate `{}`", predicate)),
+    }
+}
+
+/// Returns the value that follows the colon of a predicate.
+fn value_of<'a>(key: &str, rest: &'a str) -> Result<&'a str, String> {
+    match rest.strip_prefix(':') {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(format!("expected a value after `{}:`", key)),
+    }
+}
+
+/// Compiles the glob pattern of a `tag` or `site` predicate.
+fn parse_pattern(value: &str) -> Result<Pattern, String> {
+    Pattern::new(value).map_err(|error| format!("invalid pattern `{}`: {}", value, error))
+}
+
+/// Compiles the regular expression of a text predicate.
+fn parse_regex(value: &str) -> Result<Regex, String> {
+    Regex::new(value).map_err(|error| format!("invalid regular expression `{}`: {}", value, error))
+}
+
+/// Parses the comparison of a words predicate, such as `>=100`.
+fn parse_words(field: Option<TextField>, key: &str, rest: &str) -> Result<Condition, String> {
+    let invalid = || format!("expected a comparison such as `{}>=10`", key);
+    let operator = OPERATORS
+        .iter()
+        .find(|operator| rest.starts_with(**operator))
+        .ok_or_else(invalid)?;
+    let comparison = Comparison::from_operator(operator).ok_or_else(invalid)?;
+    let amount = rest[operator.len()..].parse().map_err(|_| invalid())?;
+    Ok(Condition::Words(field, comparison, amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites_information::question_information::FieldWords;
+
+    fn question(words: u64, tags: &[&str]) -> QuestionInformation {
+        let mut question =
+            QuestionInformation::new(words, tags.iter().map(|tag| tag.to_string()).collect());
+        question.fields = FieldWords {
+            title: 2,
+            body: words - 2,
+        };
+        question
+    }
+
+    fn texts(title: &str, body: &str) -> Vec<(TextField, String)> {
+        vec![
+            (TextField::Title, title.to_string()),
+            (TextField::Body, body.to_string()),
+        ]
+    }
+
+    fn matches(expression: &str, site: &str, question: &QuestionInformation) -> bool {
+        QuestionFilter::parse(expression).unwrap().matches(
+            Some(site),
+            &texts("A title", "A body with unsafe code"),
+            question,
+        )
+    }
+
+    #[test]
+    fn tags_are_included_and_excluded() {
+        let rust = question(10, &["rust", "unsafe"]);
+        let go = question(10, &["go"]);
+        assert!(matches("tag:rust", "site", &rust));
+        assert!(!matches("tag:rust", "site", &go));
+        assert!(matches("tag:RU*", "site", &rust));
+        assert!(!matches("tag:rust not tag:unsafe", "site", &rust));
+        assert!(matches("not tag:rust", "site", &go));
+    }
+
+    #[test]
+    fn sites_and_words_are_compared() {
+        let question = question(10, &["rust"]);
+        assert!(matches(
+            "site:*.stackexchange.com",
+            "a.stackexchange.com",
+            &question
+        ));
+        assert!(!matches(
+            "site:stackoverflow.com",
+            "a.stackexchange.com",
+            &question
+        ));
+        assert!(matches("words>=10 and words<11", "site", &question));
+        assert!(!matches("words>10", "site", &question));
+        assert!(matches("title_words=2 body_words<=8", "site", &question));
+        assert!(QuestionFilter::parse("site:a").unwrap().uses_sites());
+        assert!(!QuestionFilter::parse("tag:a or words<3")
+            .unwrap()
+            .uses_sites());
+    }
+
+    #[test]
+    fn sites_are_excluded_only_when_no_question_can_pass() {
+        let excludes = |expression: &str, site: &str| {
+            QuestionFilter::parse(expression)
+                .unwrap()
+                .excludes_site(Some(site))
+        };
+        assert!(excludes(
+            "site:rust.* and tag:rayon",
+            "python.stackexchange.com"
+        ));
+        assert!(!excludes(
+            "site:rust.* and tag:rayon",
+            "rust.stackexchange.com"
+        ));
+        assert!(!excludes(
+            "site:rust.* or tag:rayon",
+            "python.stackexchange.com"
+        ));
+        assert!(excludes(
+            "not site:python.* and words>3",
+            "python.stackexchange.com"
+        ));
+        assert!(!excludes("(site:a or site:b) (tag:x or site:c)", "b"));
+        assert!(excludes("(site:a or site:b) site:c", "b"));
+        assert!(!excludes("tag:rust", "a"));
+        assert!(QuestionFilter::parse("site:a").unwrap().excludes_site(None));
+    }
+
+    #[test]
+    fn texts_are_matched_by_field() {
+        let question = question(10, &["rust"]);
+        assert!(matches(r"text:\bunsafe\b", "site", &question));
+        assert!(!matches("title:unsafe", "site", &question));
+        assert!(matches(
+            r#"body:"with (unsafe|safe) code""#,
+            "site",
+            &question
+        ));
+        assert!(matches(r#"title:"(?i)a title""#, "site", &question));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let question = question(10, &["rust"]);
+        assert!(matches("tag:go and tag:c or tag:rust", "site", &question));
+        assert!(!matches(
+            "tag:go and (tag:c or tag:rust)",
+            "site",
+            &question
+        ));
+        assert!(matches("NOT (tag:go OR words<5)", "site", &question));
+    }
+
+    #[test]
+    fn invalid_expressions_are_errors() {
+        for expression in [
+            "",
+            "tag:",
+            "tag:rust and",
+            "(tag:rust",
+            "tag:rust)",
+            "words>many",
+            "words",
+            "color:red",
+            "text:\"unclosed",
+            "text:(",
+        ] {
+            assert!(
+                matches!(
+                    QuestionFilter::parse(expression),
+                    Err(ParsingError::ErrorInvalidFilter(_, _))
+                ),
+                "{}",
+                expression
+            );
+        }
+    }
+}
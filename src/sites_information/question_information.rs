@@ -1,12 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ranking::words_questions_ratio, report::FieldsReport, text_layout::TextField,
+    tokenizer::Tokenizer,
+};
+
 /// Question information struct.
 ///
 /// # Attributes
 ///
 /// * `words` - The number of words in the question.
+/// * `fields` - The number of words in the title and in the body of the question.
 /// * `tags` - The tags of the question.
 #[derive(Debug)]
 pub struct QuestionInformation {
-    pub words: u16,
+    pub words: u64,
+    pub fields: FieldWords,
     pub tags: Vec<String>,
 }
 
@@ -21,8 +30,137 @@ impl QuestionInformation {
     /// # Returns
     ///
     /// A new question information instance.
-    pub fn new(words: u16, tags: Vec<String>) -> Self {
-        QuestionInformation { words, tags }
+    pub fn new(words: u64, tags: Vec<String>) -> Self {
+        QuestionInformation {
+            words,
+            fields: FieldWords::default(),
+            tags,
+        }
+    }
+
+    /// Creates the information of a question from its texts, counting the words of each one.
+    /// The counters saturate instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts of the question, with the field held by each one.
+    /// * `tags` - The tags of the question.
+    /// * `tokenizer` - The tokenizer that counts the words of the texts.
+    ///
+    /// # Returns
+    ///
+    /// A new question information instance.
+    pub fn from_texts<'a, I>(texts: I, tags: Vec<String>, tokenizer: &dyn Tokenizer) -> Self
+    where
+        I: IntoIterator<Item = (TextField, &'a str)>,
+    {
+        let mut words = 0u64;
+        let mut fields = FieldWords::default();
+        for (field, text) in texts {
+            let text_words = tokenizer.count_words(text);
+            words = words.saturating_add(text_words);
+            match field {
+                TextField::Title => fields.title = fields.title.saturating_add(text_words),
+                TextField::Body => fields.body = fields.body.saturating_add(text_words),
+                TextField::Other => {}
+            }
+        }
+        QuestionInformation {
+            words,
+            fields,
+            tags,
+        }
+    }
+}
+
+/// Returns the different tags of a question, sorted, so a repeated tag is only counted once.
+///
+/// # Arguments
+///
+/// * `tags` - The tags of the question.
+///
+/// # Returns
+///
+/// The sorted tags without repetitions.
+pub fn unique_tags<T: Ord>(tags: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut tags: Vec<T> = tags.into_iter().collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Represents the texts and the tags of a question as they are read from a file, before the words
+/// of the texts are counted.
+///
+/// # Attributes
+///
+/// * `texts` - The texts of the question, with the field held by each one.
+/// * `tags` - The tags of the question.
+#[derive(Debug)]
+pub struct QuestionTexts {
+    pub texts: Vec<(TextField, String)>,
+    pub tags: Vec<String>,
+}
+
+impl QuestionTexts {
+    /// Converts the texts into the information of the question, counting their words.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokenizer` - The tokenizer that counts the words of the texts.
+    ///
+    /// # Returns
+    ///
+    /// The information of the question.
+    pub fn into_information(self, tokenizer: &dyn Tokenizer) -> QuestionInformation {
+        let texts = self
+            .texts
+            .iter()
+            .map(|(field, text)| (*field, text.as_str()));
+        QuestionInformation::from_texts(texts, self.tags, tokenizer)
+    }
+}
+
+/// Represents the amount of words in the title and in the body of a set of questions.
+///
+/// # Attributes
+///
+/// * `title` - The amount of words in the titles.
+/// * `body` - The amount of words in the bodies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldWords {
+    pub title: u64,
+    pub body: u64,
+}
+
+impl FieldWords {
+    /// Adds the words of other questions to the amounts.
+    /// The counters saturate instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The amount of words of the other questions.
+    pub fn add(&mut self, other: FieldWords) {
+        self.title = self.title.saturating_add(other.title);
+        self.body = self.body.saturating_add(other.body);
+    }
+
+    /// Generates the report of the words of each field.
+    ///
+    /// # Arguments
+    ///
+    /// * `questions` - The amount of questions the words belong to.
+    ///
+    /// # Returns
+    ///
+    /// The words of each field and their ratio of words/questions.
+    pub fn generate_report(&self, questions: u64) -> FieldsReport {
+        FieldsReport {
+            body_ratio: words_questions_ratio(self.body, questions),
+            body_words: self.body,
+            title_ratio: words_questions_ratio(self.title, questions),
+            title_words: self.title,
+        }
     }
 }
 
@@ -35,9 +173,21 @@ mod tests {
         let question_information =
             QuestionInformation::new(10, vec!["tag1".to_string(), "tag2".to_string()]);
         assert_eq!(question_information.words, 10);
+        assert_eq!(question_information.fields, FieldWords::default());
         assert_eq!(
             question_information.tags,
             vec!["tag1".to_string(), "tag2".to_string()]
         );
     }
+
+    #[test]
+    fn field_words_are_reported_per_question() {
+        let mut fields = FieldWords { title: 4, body: 10 };
+        fields.add(FieldWords { title: 2, body: 20 });
+        let report = fields.generate_report(3);
+        assert_eq!(report.title_words, 6);
+        assert_eq!(report.title_ratio, Some(2.0));
+        assert_eq!(report.body_ratio, Some(10.0));
+        assert_eq!(FieldWords::default().generate_report(0).body_ratio, None);
+    }
 }
@@ -0,0 +1,294 @@
+use super::{
+    input_schema::{InputSchema, Post},
+    question_filter::QuestionFilter,
+    question_information::{QuestionInformation, QuestionTexts},
+    site::Site,
+    text_layout::TextLayout,
+    tokenizer::{Tokenizer, WhitespaceTokenizer},
+};
+
+/// Parses the lines of the files into the information of their questions.
+///
+/// # Attributes
+///
+/// * `schema` - The shape of the JSON questions of the lines.
+/// * `layout` - The field held by each of the texts of the questions.
+/// * `tokenizer` - The tokenizer that counts the words of the texts.
+/// * `filter` - The filter the questions must pass to be aggregated, if there is one.
+/// * `tag_pairs` - Whether the sites count the questions in which each pair of tags appeared.
+#[derive(Debug)]
+pub struct QuestionParser {
+    schema: InputSchema,
+    layout: TextLayout,
+    tokenizer: Box<dyn Tokenizer>,
+    filter: Option<QuestionFilter>,
+    tag_pairs: bool,
+}
+
+impl QuestionParser {
+    /// Creates a parser of the questions of the default schema with the provided layout and tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The field held by each of the texts of the questions.
+    /// * `tokenizer` - The tokenizer that counts the words of the texts.
+    ///
+    /// # Returns
+    ///
+    /// A new QuestionParser instance.
+    pub fn new(layout: TextLayout, tokenizer: Box<dyn Tokenizer>) -> Self {
+        QuestionParser {
+            schema: InputSchema::default(),
+            layout,
+            tokenizer,
+            filter: None,
+            tag_pairs: false,
+        }
+    }
+
+    /// Returns the shape of the JSON questions of the lines.
+    ///
+    /// # Returns
+    ///
+    /// The schema of the questions.
+    pub fn schema(&self) -> InputSchema {
+        self.schema
+    }
+
+    /// Returns the field held by each of the texts of the questions.
+    ///
+    /// # Returns
+    ///
+    /// The layout of the texts of the questions.
+    pub fn layout(&self) -> &TextLayout {
+        &self.layout
+    }
+
+    /// Returns the name of the tokenizer that counts the words of the texts.
+    ///
+    /// # Returns
+    ///
+    /// The name of the tokenizer.
+    pub fn tokenizer_name(&self) -> &'static str {
+        self.tokenizer.name()
+    }
+
+    /// Returns the filter the questions must pass to be aggregated.
+    ///
+    /// # Returns
+    ///
+    /// The filter of the questions, if there is one.
+    pub fn filter(&self) -> Option<&QuestionFilter> {
+        self.filter.as_ref()
+    }
+
+    /// Returns whether the sites count the questions in which each pair of tags appeared.
+    ///
+    /// # Returns
+    ///
+    /// True if the pairs of tags are counted.
+    pub fn counts_tag_pairs(&self) -> bool {
+        self.tag_pairs
+    }
+
+    /// Sets the shape of the JSON questions of the lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the questions.
+    pub fn set_schema(&mut self, schema: InputSchema) {
+        self.schema = schema;
+    }
+
+    /// Sets the field held by each of the texts of the questions.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout of the texts of the questions.
+    pub fn set_layout(&mut self, layout: TextLayout) {
+        self.layout = layout;
+    }
+
+    /// Sets the tokenizer that counts the words of the texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokenizer` - The tokenizer.
+    pub fn set_tokenizer(&mut self, tokenizer: Box<dyn Tokenizer>) {
+        self.tokenizer = tokenizer;
+    }
+
+    /// Sets the filter the questions must pass to be aggregated.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter of the questions, or None to aggregate every question.
+    pub fn set_filter(&mut self, filter: Option<QuestionFilter>) {
+        self.filter = filter;
+    }
+
+    /// Sets whether the sites count the questions in which each pair of tags appeared. The pairs
+    /// are only needed by the related tags, and counting them grows with the square of the tags
+    /// of each question.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_pairs` - Whether the pairs of tags are counted.
+    pub fn set_tag_pairs(&mut self, tag_pairs: bool) {
+        self.tag_pairs = tag_pairs;
+    }
+
+    /// Creates an empty site for the questions of this parser, counting the pairs of their tags
+    /// if they are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the site.
+    ///
+    /// # Returns
+    ///
+    /// A new site instance.
+    pub fn new_site(&self, name: Option<String>) -> Site {
+        if self.tag_pairs {
+            Site::with_tag_pairs(name)
+        } else {
+            Site::new(name)
+        }
+    }
+
+    /// Returns whether no question of the provided site can pass the filter, so the files of the
+    /// site do not have to be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The name of the site.
+    ///
+    /// # Returns
+    ///
+    /// True if the filter rejects every question of the site.
+    pub fn excludes_site(&self, site: Option<&str>) -> bool {
+        self.filter
+            .as_ref()
+            .is_some_and(|filter| filter.excludes_site(site))
+    }
+
+    /// Parses a line with a JSON question into the information of the question.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line with the question.
+    /// * `site` - The name of the site of the question, used by the filter.
+    ///
+    /// # Returns
+    ///
+    /// A result with the information of the question, or None if the line does not hold a question
+    /// or the question does not pass the filter, or the error found while parsing it.
+    pub fn parse(
+        &self,
+        line: &str,
+        site: Option<&str>,
+    ) -> serde_json::Result<Option<QuestionInformation>> {
+        let question = self.schema.parse(line, &self.layout)?;
+        Ok(question.and_then(|question| self.count_words(question, site)))
+    }
+
+    /// Converts a post of a `Posts.xml` dump into the information of its question.
+    /// The posts are read the same way regardless of the schema of the lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - The post.
+    /// * `site` - The name of the site of the post, used by the filter.
+    ///
+    /// # Returns
+    ///
+    /// The information of the question, or None if the post is not a question or it does not pass
+    /// the filter.
+    pub fn parse_post(&self, post: Post, site: Option<&str>) -> Option<QuestionInformation> {
+        self.count_words(post.into_texts()?, site)
+    }
+
+    /// Counts the words of the texts of a question and checks it against the filter.
+    fn count_words(
+        &self,
+        question: QuestionTexts,
+        site: Option<&str>,
+    ) -> Option<QuestionInformation> {
+        let texts = question
+            .texts
+            .iter()
+            .map(|(field, text)| (*field, text.as_str()));
+        let information =
+            QuestionInformation::from_texts(texts, question.tags, self.tokenizer.as_ref());
+        match &self.filter {
+            Some(filter) if !filter.matches(site, &question.texts, &information) => None,
+            _ => Some(information),
+        }
+    }
+}
+
+impl Default for QuestionParser {
+    /// Creates a parser for the flax-sentence-embeddings StackExchange datasets that counts the
+    /// words between whitespace.
+    ///
+    /// # Returns
+    ///
+    /// A new QuestionParser instance.
+    fn default() -> Self {
+        QuestionParser::new(TextLayout::default(), Box::new(WhitespaceTokenizer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites_information::tokenizer::ProseTokenizer;
+
+    const LINE: &str =
+        r#"{"texts": ["A <b>bold</b> title", "Some `code` here"], "tags": ["tag1"]}"#;
+
+    #[test]
+    fn lines_are_parsed_with_the_tokenizer() {
+        let mut parser = QuestionParser::default();
+        assert_eq!(parser.parse(LINE, None).unwrap().unwrap().words, 6);
+
+        parser.set_tokenizer(Box::new(ProseTokenizer));
+        let question = parser.parse(LINE, None).unwrap().unwrap();
+        assert_eq!(question.words, 5);
+        assert_eq!(question.fields.title, 3);
+        assert_eq!(question.tags, vec!["tag1".to_string()]);
+    }
+
+    #[test]
+    fn lines_are_parsed_with_the_schema() {
+        let mut parser = QuestionParser::default();
+        parser.set_schema(InputSchema::Fields);
+        assert!(parser.parse(LINE, None).is_err());
+        let question = parser
+            .parse(
+                r#"{"title": "A title", "body": "A body", "tags": "<a>"}"#,
+                None,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(question.words, 4);
+    }
+
+    #[test]
+    fn questions_that_do_not_pass_the_filter_are_skipped() {
+        let mut parser = QuestionParser::default();
+        parser.set_filter(Some(
+            QuestionFilter::parse("tag:tag1 and site:s* and title:bold").unwrap(),
+        ));
+        assert!(parser.parse(LINE, Some("site")).unwrap().is_some());
+        assert!(parser.parse(LINE, Some("other")).unwrap().is_none());
+        assert!(parser.parse(LINE, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_lines_are_errors() {
+        assert!(QuestionParser::default()
+            .parse("{\"texts\": []}", None)
+            .is_err());
+    }
+}
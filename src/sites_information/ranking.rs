@@ -0,0 +1,459 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+/// Default amount of entries kept by a ranking.
+pub const DEFAULT_RANKING_LIMIT: usize = 10;
+
+/// Enum that represents the value by which the sites or tags are ranked.
+///
+/// # Variants
+///
+/// * `Questions` - The amount of questions.
+/// * `Words` - The total amount of words of the questions.
+/// * `Ratio` - The ratio of words/questions.
+/// * `MedianWords` - The median amount of words of the questions.
+/// * `CoOccurrences` - The amount of times a tag appeared together with another tag. For a site,
+///   the amount of pairs of tags that appeared together in its questions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingMetric {
+    Questions,
+    Words,
+    Ratio,
+    MedianWords,
+    CoOccurrences,
+}
+
+impl RankingMetric {
+    /// Returns the metric with the provided name, as it is written in the reports.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the metric.
+    ///
+    /// # Returns
+    ///
+    /// The metric, or None if there is no metric with that name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "questions" => Some(RankingMetric::Questions),
+            "words" => Some(RankingMetric::Words),
+            "ratio" => Some(RankingMetric::Ratio),
+            "median_words" => Some(RankingMetric::MedianWords),
+            "co_occurrences" => Some(RankingMetric::CoOccurrences),
+            _ => None,
+        }
+    }
+}
+
+/// Enum that represents the direction of a ranking.
+///
+/// # Variants
+///
+/// * `Descending` - The entries with the highest values go first.
+/// * `Ascending` - The entries with the lowest values go first.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingOrder {
+    Descending,
+    Ascending,
+}
+
+/// Trait of the entries that can be ranked, like sites and tags.
+pub trait Rankable {
+    /// Returns the amount of questions of the entry, used by the minimum-question threshold.
+    /// Entries without questions are never ranked.
+    ///
+    /// # Returns
+    ///
+    /// The amount of questions.
+    fn question_count(&self) -> u64;
+
+    /// Returns the amount of words of the questions of the entry, used by the Bayesian average.
+    ///
+    /// # Returns
+    ///
+    /// The amount of words.
+    fn word_count(&self) -> u64;
+
+    /// Returns the value of the provided metric for the entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The metric to calculate.
+    ///
+    /// # Returns
+    ///
+    /// The value of the metric, or None if the entry has no value for it.
+    fn metric_value(&self, metric: RankingMetric) -> Option<f64>;
+}
+
+/// Calculates the ratio between an amount of words and an amount of questions.
+///
+/// # Arguments
+///
+/// * `words` - The amount of words.
+/// * `questions` - The amount of questions.
+///
+/// # Returns
+///
+/// The ratio of words/questions, or None if there are no questions.
+pub fn words_questions_ratio(words: u64, questions: u64) -> Option<f64> {
+    if questions == 0 {
+        None
+    } else {
+        Some(words as f64 / questions as f64)
+    }
+}
+
+/// Represents how a list of sites or tags is ranked.
+///
+/// # Attributes
+///
+/// * `metric` - The value by which the entries are ranked.
+/// * `order` - Whether the highest or the lowest values go first.
+/// * `min_questions` - The minimum amount of questions an entry needs to be ranked.
+/// * `limit` - The maximum amount of entries kept.
+/// * `prior_weight` - The amount of questions of the prior of the Bayesian average of the ratio.
+///   If it is zero, the ratio of words/questions is used without smoothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ranking {
+    pub metric: RankingMetric,
+    pub order: RankingOrder,
+    pub min_questions: u64,
+    pub limit: usize,
+    pub prior_weight: f64,
+}
+
+impl Ranking {
+    /// Creates a descending ranking by the provided metric, without a minimum amount of
+    /// questions and with the default limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The value by which the entries are ranked.
+    ///
+    /// # Returns
+    ///
+    /// A new Ranking instance.
+    pub fn new(metric: RankingMetric) -> Self {
+        Ranking {
+            metric,
+            order: RankingOrder::Descending,
+            min_questions: 0,
+            limit: DEFAULT_RANKING_LIMIT,
+            prior_weight: 0.0,
+        }
+    }
+
+    /// Creates the ranking of the most chatty entries: the highest ratios of words/questions.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum amount of entries kept.
+    ///
+    /// # Returns
+    ///
+    /// A new Ranking instance.
+    pub fn chatty(limit: u8) -> Self {
+        Ranking::new(RankingMetric::Ratio).limit(limit as usize)
+    }
+
+    /// Sets the direction of the ranking.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - Whether the highest or the lowest values go first.
+    ///
+    /// # Returns
+    ///
+    /// The ranking with the order set.
+    pub fn order(mut self, order: RankingOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the minimum amount of questions an entry needs to be ranked.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_questions` - The minimum amount of questions.
+    ///
+    /// # Returns
+    ///
+    /// The ranking with the threshold set.
+    pub fn min_questions(mut self, min_questions: u64) -> Self {
+        self.min_questions = min_questions;
+        self
+    }
+
+    /// Sets the maximum amount of entries kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum amount of entries.
+    ///
+    /// # Returns
+    ///
+    /// The ranking with the limit set.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets the weight of the prior of the Bayesian average used when ranking by ratio.
+    /// The ratio of an entry becomes `(weight * mean + words) / (weight + questions)`, so
+    /// entries with few questions are pulled toward the mean ratio instead of topping the ranking.
+    ///
+    /// # Arguments
+    ///
+    /// * `prior_weight` - The amount of questions of the prior. Zero disables the smoothing.
+    ///
+    /// # Returns
+    ///
+    /// The ranking with the prior weight set.
+    pub fn prior_weight(mut self, prior_weight: f64) -> Self {
+        self.prior_weight = prior_weight.max(0.0);
+        self
+    }
+
+    /// Ranks the provided entries and returns the names of the first ones.
+    /// Entries without questions, below the minimum amount of questions or without a value for
+    /// the metric are skipped. If the ratio is smoothed, the mean of the prior is the ratio of
+    /// words/questions of all the entries together.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The name and the value of each entry.
+    ///
+    /// # Returns
+    ///
+    /// The names of the ranked entries, up to the limit.
+    pub fn rank<'a, T, I>(&self, entries: I) -> Vec<String>
+    where
+        T: Rankable + 'a,
+        I: IntoIterator<Item = (&'a str, &'a T)>,
+    {
+        let entries: Vec<(&str, &T)> = entries.into_iter().collect();
+        let (questions, words) =
+            entries
+                .iter()
+                .fold((0u64, 0u64), |(questions, words), (_, entry)| {
+                    (
+                        questions.saturating_add(entry.question_count()),
+                        words.saturating_add(entry.word_count()),
+                    )
+                });
+        let mean = words_questions_ratio(words, questions).unwrap_or(0.0);
+        self.rank_with_mean(entries, mean)
+    }
+
+    /// Ranks the provided entries and returns the names of the first ones, using the provided
+    /// mean ratio as the prior of the Bayesian average if the ratio is smoothed.
+    /// Entries without questions, below the minimum amount of questions or without a value for
+    /// the metric are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The name and the value of each entry.
+    /// * `mean` - The ratio of words/questions the entries with few questions are pulled toward.
+    ///
+    /// # Returns
+    ///
+    /// The names of the ranked entries, up to the limit.
+    pub fn rank_with_mean<'a, T, I>(&self, entries: I, mean: f64) -> Vec<String>
+    where
+        T: Rankable + 'a,
+        I: IntoIterator<Item = (&'a str, &'a T)>,
+    {
+        let mut ranked: Vec<(&str, f64)> = entries
+            .into_iter()
+            .filter(|(_, entry)| entry.question_count() > 0)
+            .filter(|(_, entry)| entry.question_count() >= self.min_questions)
+            .filter_map(|(name, entry)| Some((name, self.value_of(entry, mean)?)))
+            .collect();
+        ranked.sort_by(|a, b| compare_entries(*a, *b, self.order));
+        ranked
+            .into_iter()
+            .take(self.limit)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Returns the value by which an entry is ranked, or None if it has no value for the metric.
+    fn value_of<T: Rankable>(&self, entry: &T, mean: f64) -> Option<f64> {
+        if self.metric != RankingMetric::Ratio || self.prior_weight == 0.0 {
+            return entry.metric_value(self.metric);
+        }
+        let words = entry.word_count() as f64;
+        let questions = entry.question_count() as f64;
+        Some((self.prior_weight * mean + words) / (self.prior_weight + questions))
+    }
+}
+
+/// Compares two named entries by their value in the provided order.
+/// Ties are broken by name and values that are not a number go last in both orders,
+/// so the order is total and does not depend on the order in which the entries were collected.
+///
+/// # Arguments
+///
+/// * `a` - The name and value of the first entry.
+/// * `b` - The name and value of the second entry.
+/// * `order` - Whether the highest or the lowest values go first.
+///
+/// # Returns
+///
+/// The ordering of the first entry with respect to the second one.
+pub fn compare_entries(a: (&str, f64), b: (&str, f64), order: RankingOrder) -> Ordering {
+    let by_value = match (a.1.is_nan(), b.1.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => match order {
+            RankingOrder::Descending => b.1.total_cmp(&a.1),
+            RankingOrder::Ascending => a.1.total_cmp(&b.1),
+        },
+    };
+    by_value.then_with(|| a.0.cmp(b.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Entry {
+        questions: u64,
+        words: u64,
+    }
+
+    impl Rankable for Entry {
+        fn question_count(&self) -> u64 {
+            self.questions
+        }
+
+        fn word_count(&self) -> u64 {
+            self.words
+        }
+
+        fn metric_value(&self, metric: RankingMetric) -> Option<f64> {
+            match metric {
+                RankingMetric::Questions => Some(self.questions as f64),
+                RankingMetric::Words => Some(self.words as f64),
+                RankingMetric::Ratio => words_questions_ratio(self.words, self.questions),
+                _ => None,
+            }
+        }
+    }
+
+    fn entries() -> Vec<(&'static str, Entry)> {
+        vec![
+            (
+                "a",
+                Entry {
+                    questions: 1,
+                    words: 50,
+                },
+            ),
+            (
+                "b",
+                Entry {
+                    questions: 10,
+                    words: 100,
+                },
+            ),
+            (
+                "c",
+                Entry {
+                    questions: 5,
+                    words: 100,
+                },
+            ),
+            (
+                "empty",
+                Entry {
+                    questions: 0,
+                    words: 0,
+                },
+            ),
+        ]
+    }
+
+    fn rank(ranking: Ranking) -> Vec<String> {
+        let entries = entries();
+        ranking.rank(entries.iter().map(|(name, entry)| (*name, entry)))
+    }
+
+    #[test]
+    fn rankings_use_the_metric_and_the_order() {
+        assert_eq!(rank(Ranking::chatty(2)), vec!["a", "c"]);
+        assert_eq!(
+            rank(Ranking::new(RankingMetric::Questions).limit(3)),
+            vec!["b", "c", "a"]
+        );
+        assert_eq!(
+            rank(Ranking::new(RankingMetric::Ratio).order(RankingOrder::Ascending)),
+            vec!["b", "c", "a"]
+        );
+    }
+
+    #[test]
+    fn entries_without_questions_or_value_are_never_ranked() {
+        assert_eq!(
+            rank(Ranking::new(RankingMetric::Words).order(RankingOrder::Ascending)),
+            vec!["a", "b", "c"]
+        );
+        assert!(rank(Ranking::new(RankingMetric::MedianWords)).is_empty());
+        assert_eq!(words_questions_ratio(10, 0), None);
+        assert_eq!(words_questions_ratio(10, 4), Some(2.5));
+    }
+
+    #[test]
+    fn ties_are_broken_by_name() {
+        assert_eq!(
+            rank(Ranking::new(RankingMetric::Words).limit(2)),
+            vec!["b", "c"]
+        );
+    }
+
+    #[test]
+    fn entries_below_the_threshold_are_skipped() {
+        assert_eq!(rank(Ranking::chatty(10).min_questions(5)), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn bayesian_average_pulls_entries_with_few_questions_toward_the_mean() {
+        assert_eq!(rank(Ranking::chatty(1)), vec!["a"]);
+        // The mean is 250 / 16 = 15.625, so "a" becomes (10 * 15.625 + 50) / 11 = 18.75
+        // and "c" becomes (10 * 15.625 + 100) / 15 = 17.08.
+        assert_eq!(rank(Ranking::chatty(2).prior_weight(10.0)), vec!["a", "c"]);
+        // With a mean of 5, "a" becomes (10 * 5 + 50) / 11 = 9.09 and "c" becomes
+        // (10 * 5 + 100) / 15 = 10, so the single long question no longer tops the ranking.
+        let entries = entries();
+        let ranked = Ranking::chatty(1)
+            .prior_weight(10.0)
+            .rank_with_mean(entries.iter().map(|(name, entry)| (*name, entry)), 5.0);
+        assert_eq!(ranked, vec!["c"]);
+    }
+
+    #[test]
+    fn values_that_are_not_a_number_go_last() {
+        for order in [RankingOrder::Descending, RankingOrder::Ascending] {
+            for nan in [f64::NAN, -f64::NAN] {
+                assert_eq!(
+                    compare_entries(("a", nan), ("b", 1.0), order),
+                    Ordering::Greater
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn metrics_are_found_by_name() {
+        assert_eq!(
+            RankingMetric::from_name("median_words"),
+            Some(RankingMetric::MedianWords)
+        );
+        assert_eq!(RankingMetric::from_name("length"), None);
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    diagnostics::FileDiagnostics,
+    ranking::{RankingMetric, RankingOrder},
+};
+
+/// Represents the summary of the distribution of the amount of words of a set of questions.
+/// The minimum, maximum and standard deviation are exact, the quantiles are approximations.
+///
+/// # Attributes
+///
+/// * `max` - The highest amount of words of a question.
+/// * `median` - The median amount of words.
+/// * `min` - The lowest amount of words of a question.
+/// * `p90` - The 90th percentile of the amount of words.
+/// * `p99` - The 99th percentile of the amount of words.
+/// * `std_dev` - The population standard deviation of the amount of words.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DistributionReport {
+    pub max: u64,
+    pub median: f64,
+    pub min: u64,
+    pub p90: f64,
+    pub p99: f64,
+    pub std_dev: f64,
+}
+
+/// Represents the amount of words in the title and in the body of a set of questions.
+///
+/// # Attributes
+///
+/// * `body_ratio` - The ratio of body words/questions, if there are questions.
+/// * `body_words` - The amount of words in the bodies.
+/// * `title_ratio` - The ratio of title words/questions, if there are questions.
+/// * `title_words` - The amount of words in the titles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldsReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_ratio: Option<f64>,
+    pub body_words: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_ratio: Option<f64>,
+    pub title_words: u64,
+}
+
+/// Represents the report of a tag.
+///
+/// # Attributes
+///
+/// * `distribution` - The summary of the amount of words of the questions with the tag.
+/// * `fields` - The amount of words in the titles and bodies of the questions with the tag.
+/// * `questions` - The amount of questions with the tag.
+/// * `words` - The amount of words of the questions with the tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<DistributionReport>,
+    #[serde(default)]
+    pub fields: FieldsReport,
+    pub questions: u64,
+    pub words: u64,
+}
+
+/// Represents the report of a site.
+///
+/// # Attributes
+///
+/// * `chatty_tags` - The tags of the site with the highest ratio of words/questions.
+/// * `distribution` - The summary of the amount of words of the questions of the site, if it has any.
+/// * `fields` - The amount of words in the titles and bodies of the questions of the site.
+/// * `questions` - The amount of questions of the site.
+/// * `tags` - The report of each tag of the site, by name.
+/// * `words` - The amount of words of the questions of the site.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SiteReport {
+    pub chatty_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<DistributionReport>,
+    #[serde(default)]
+    pub fields: FieldsReport,
+    pub questions: u64,
+    pub tags: BTreeMap<String, TagReport>,
+    pub words: u64,
+}
+
+/// Represents the totals of the report.
+///
+/// # Attributes
+///
+/// * `chatty_sites` - The sites with the highest ratio of words/questions.
+/// * `chatty_tags` - The tags of all the sites with the highest ratio of words/questions.
+/// * `empty_sites` - The sites without valid questions, which are excluded from the rankings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TotalsReport {
+    pub chatty_sites: Vec<String>,
+    pub chatty_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub empty_sites: Vec<String>,
+}
+
+/// Represents a leaderboard of sites and tags ranked by a metric.
+///
+/// # Attributes
+///
+/// * `metric` - The value by which the sites and tags are ranked.
+/// * `min_questions` - The minimum amount of questions a site or tag needs to be ranked.
+/// * `order` - Whether the highest or the lowest values go first.
+/// * `prior_weight` - The weight of the prior of the Bayesian average of the ratio, zero if it is not smoothed.
+/// * `sites` - The ranked sites.
+/// * `tags` - The ranked tags of all the sites.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardReport {
+    pub metric: RankingMetric,
+    pub min_questions: u64,
+    pub order: RankingOrder,
+    #[serde(default)]
+    pub prior_weight: f64,
+    pub sites: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+/// Represents a tag that appeared together with another tag.
+///
+/// # Attributes
+///
+/// * `questions` - The amount of questions in which both tags appeared.
+/// * `tag` - The name of the related tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedTagReport {
+    pub questions: u64,
+    pub tag: String,
+}
+
+/// Represents the tags that appeared together the most with each tag.
+///
+/// # Attributes
+///
+/// * `sites` - The related tags of each tag of each site, by site and tag.
+/// * `tags` - The related tags of each tag of all the sites, by tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedTagsReport {
+    pub sites: BTreeMap<String, BTreeMap<String, Vec<RelatedTagReport>>>,
+    pub tags: BTreeMap<String, Vec<RelatedTagReport>>,
+}
+
+/// Represents the report of the problems found while loading the sites.
+///
+/// # Attributes
+///
+/// * `files` - The diagnostics of the files with problems, ordered by path.
+/// * `merged_sites` - The paths of the files of each site that was stored in more than one file, by name.
+/// * `skipped_files` - The amount of files that could not be read.
+/// * `skipped_lines` - The amount of lines that were skipped in all the files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub files: Vec<FileDiagnostics>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub merged_sites: BTreeMap<String, Vec<String>>,
+    pub skipped_files: u64,
+    pub skipped_lines: u64,
+}
+
+/// Represents the full report of a collection of sites.
+///
+/// # Attributes
+///
+/// * `diagnostics` - The problems found while loading the sites, if they were requested.
+/// * `leaderboards` - The additional rankings of sites and tags that were requested, by name.
+/// * `padron` - The padron number.
+/// * `related_tags` - The tags that appeared together the most with each tag, if they were requested.
+/// * `sites` - The report of each site, by name.
+/// * `tags` - The report of each tag of all the sites, by name.
+/// * `totals` - The totals of all the sites.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<DiagnosticsReport>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub leaderboards: BTreeMap<String, LeaderboardReport>,
+    pub padron: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related_tags: Option<RelatedTagsReport>,
+    pub sites: BTreeMap<String, SiteReport>,
+    pub tags: BTreeMap<String, TagReport>,
+    pub totals: TotalsReport,
+}
+
+impl Report {
+    /// Parses a report from its JSON representation. If the JSON is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The JSON with the report.
+    ///
+    /// # Returns
+    ///
+    /// A result with the report or the error found while parsing it.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Generates the pretty printed JSON representation of the report.
+    ///
+    /// # Returns
+    ///
+    /// A result with the JSON or the error found while serializing the report.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sites_information::diagnostics::{DiagnosticKind, LineDiagnostic};
+
+    fn tag(questions: u64, words: u64) -> TagReport {
+        TagReport {
+            distribution: None,
+            fields: FieldsReport::default(),
+            questions,
+            words,
+        }
+    }
+
+    fn sample_report() -> Report {
+        let tags = BTreeMap::from([("rust".to_string(), tag(2, 30))]);
+        let site = SiteReport {
+            chatty_tags: vec!["rust".to_string()],
+            distribution: Some(DistributionReport {
+                max: 20,
+                median: 10.0,
+                min: 10,
+                p90: 20.0,
+                p99: 20.0,
+                std_dev: 5.0,
+            }),
+            fields: FieldsReport {
+                body_ratio: Some(10.0),
+                body_words: 20,
+                title_ratio: Some(5.0),
+                title_words: 10,
+            },
+            questions: 2,
+            tags: tags.clone(),
+            words: 30,
+        };
+        Report {
+            diagnostics: None,
+            leaderboards: BTreeMap::new(),
+            padron: "108225".to_string(),
+            related_tags: None,
+            sites: BTreeMap::from([("site.jsonl".to_string(), site)]),
+            tags,
+            totals: TotalsReport {
+                chatty_sites: vec!["site.jsonl".to_string()],
+                chatty_tags: vec!["rust".to_string()],
+                empty_sites: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let mut report = sample_report();
+        assert_eq!(
+            Report::from_json(&report.to_json().unwrap()).unwrap(),
+            report
+        );
+
+        let mut file = FileDiagnostics::new("broken.jsonl".to_string());
+        file.add_line_error(LineDiagnostic::new(
+            3,
+            DiagnosticKind::MalformedLine,
+            "expected value".to_string(),
+        ));
+        report.diagnostics = Some(DiagnosticsReport {
+            files: vec![file],
+            merged_sites: BTreeMap::from([(
+                "site".to_string(),
+                vec!["a/site.jsonl".to_string(), "b/site.jsonl".to_string()],
+            )]),
+            skipped_files: 0,
+            skipped_lines: 1,
+        });
+        report.leaderboards.insert(
+            "least_verbose".to_string(),
+            LeaderboardReport {
+                metric: RankingMetric::MedianWords,
+                min_questions: 2,
+                order: RankingOrder::Ascending,
+                prior_weight: 0.0,
+                sites: vec!["site.jsonl".to_string()],
+                tags: vec!["rust".to_string()],
+            },
+        );
+        let related = vec![RelatedTagReport {
+            questions: 1,
+            tag: "rayon".to_string(),
+        }];
+        report.related_tags = Some(RelatedTagsReport {
+            sites: BTreeMap::from([(
+                "site.jsonl".to_string(),
+                BTreeMap::from([("rust".to_string(), related.clone())]),
+            )]),
+            tags: BTreeMap::from([("rust".to_string(), related)]),
+        });
+        assert_eq!(
+            Report::from_json(&report.to_json().unwrap()).unwrap(),
+            report
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_omitted_when_absent() {
+        let json = sample_report().to_json().unwrap();
+        assert!(!json.contains("diagnostics"));
+        assert!(!json.contains("leaderboards"));
+        assert!(!json.contains("related_tags"));
+        assert!(json.contains("\"padron\": \"108225\""));
+    }
+}
@@ -1,14 +1,38 @@
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
 
-use super::{question_information::QuestionInformation, tags_collection::TagsCollection};
+use super::{
+    co_occurrence_matrix::CoOccurrenceMatrix,
+    question_information::{unique_tags, FieldWords, QuestionInformation},
+    ranking::{words_questions_ratio, Rankable, Ranking, RankingMetric},
+    report::SiteReport,
+    tags_collection::TagsCollection,
+    word_distribution::WordDistribution,
+};
 
 /// Represents a site with its information.
-#[derive(Debug)]
+///
+/// # Attributes
+///
+/// * `name` - The name of the site.
+/// * `question_count` - The amount of questions of the site.
+/// * `word_count` - The amount of words of the questions of the site.
+/// * `co_occurrences` - The amount of pairs of tags that appeared together in the questions,
+///   counted only while the site has no `tag_pairs`.
+/// * `distribution` - The distribution of the amount of words of the questions.
+/// * `fields` - The amount of words in the titles and bodies of the questions.
+/// * `tags` - The tags of the questions of the site.
+/// * `tag_pairs` - The amount of questions in which each pair of tags appeared together,
+///   if it is counted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Site {
     name: Option<String>,
-    question_count: u32,
-    word_count: u32,
+    question_count: u64,
+    word_count: u64,
+    co_occurrences: u64,
+    distribution: WordDistribution,
+    fields: FieldWords,
     tags: TagsCollection,
+    tag_pairs: Option<CoOccurrenceMatrix>,
 }
 
 impl Site {
@@ -26,30 +50,78 @@ impl Site {
             name,
             question_count: 0,
             word_count: 0,
+            co_occurrences: 0,
+            distribution: WordDistribution::new(),
+            fields: FieldWords::default(),
             tags: TagsCollection::new(),
+            tag_pairs: None,
+        }
+    }
+
+    /// Creates a new site with the given name that counts the questions in which each pair of tags
+    /// appeared together, needed by the related tags.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the site.
+    ///
+    /// # Returns
+    ///
+    /// A new site instance.
+    pub fn with_tag_pairs(name: Option<String>) -> Self {
+        Site {
+            tag_pairs: Some(CoOccurrenceMatrix::new()),
+            ..Site::new(name)
         }
     }
 
     /// Merges the information of the site with the information of another site.
+    /// The counters saturate instead of overflowing.
     ///
     /// # Arguments
     ///
     /// * `other` - The other site to merge with
     pub fn merge(&mut self, other: Self) {
-        self.question_count += other.question_count;
-        self.word_count += other.word_count;
+        self.question_count = self.question_count.saturating_add(other.question_count);
+        self.word_count = self.word_count.saturating_add(other.word_count);
+        self.co_occurrences = self.co_occurrences.saturating_add(other.co_occurrences);
+        self.distribution.merge(&other.distribution);
+        self.fields.add(other.fields);
         self.tags.merge(other.tags);
+        match (&mut self.tag_pairs, other.tag_pairs) {
+            (Some(tag_pairs), Some(other_pairs)) => tag_pairs.merge(other_pairs),
+            (tag_pairs, other_pairs) => {
+                // The pairs are only kept if both sites count them, otherwise only their amount is.
+                let pairs = [tag_pairs.take(), other_pairs]
+                    .iter()
+                    .flatten()
+                    .fold(0u64, |total, pairs| total.saturating_add(pairs.pairs()));
+                self.co_occurrences = self.co_occurrences.saturating_add(pairs);
+            }
+        }
     }
 
     /// Adds a question to the site.
+    /// The counters saturate instead of overflowing.
     ///
     /// # Arguments
     ///
     /// * `question` - The question to add.
     pub fn add_question(&mut self, question: QuestionInformation) {
-        self.question_count += 1;
-        self.word_count += question.words as u32;
-        self.tags.add_tags(question.tags, question.words as u32);
+        self.question_count = self.question_count.saturating_add(1);
+        self.word_count = self.word_count.saturating_add(question.words);
+        match &mut self.tag_pairs {
+            Some(tag_pairs) => tag_pairs.add_tags(&question.tags),
+            None => {
+                let tags = unique_tags(&question.tags).len() as u64;
+                let pairs = tags.saturating_mul(tags.saturating_sub(1)) / 2;
+                self.co_occurrences = self.co_occurrences.saturating_add(pairs);
+            }
+        }
+        self.distribution.add(question.words);
+        self.fields.add(question.fields);
+        self.tags
+            .add_tags(question.tags, question.words, question.fields);
     }
 
     /// Returns the name of the site.
@@ -61,18 +133,37 @@ impl Site {
         self.name.clone()
     }
 
-    /// Generates a JSON with the site information.
+    /// Sets the name of the site, used when its files are assigned to another site.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the site.
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Generates the report of the site.
+    /// If the chatty tags ranking smooths the ratio, the tags are pulled toward the ratio of the site.
+    ///
+    /// # Arguments
+    ///
+    /// * `chatty_tags` - The ranking of the chatty tags included in the report.
     ///
     /// # Returns
     ///
-    /// A JSON with the site information.
-    pub fn generate_json(&self) -> Value {
-        let mut site_data = Value::Object(serde_json::Map::new());
-        site_data["questions"] = Value::from(self.question_count);
-        site_data["words"] = Value::from(self.word_count);
-        site_data["tags"] = self.tags.generate_json();
-        site_data["chatty_tags"] = self.tags.generate_chatty_tags_json(10);
-        site_data
+    /// The report of the site.
+    pub fn generate_report(&self, chatty_tags: &Ranking) -> SiteReport {
+        SiteReport {
+            chatty_tags: self.tags.rank_tags_with_mean(
+                chatty_tags,
+                self.calculate_words_questions_ratio().unwrap_or(0.0),
+            ),
+            distribution: self.distribution.generate_report(),
+            fields: self.fields.generate_report(self.question_count),
+            questions: self.question_count,
+            tags: self.tags.generate_report(),
+            words: self.word_count,
+        }
     }
 
     /// Returns the tags of the questions in the site.
@@ -84,13 +175,85 @@ impl Site {
         &self.tags
     }
 
+    /// Returns the pairs of tags of the questions in the site.
+    ///
+    /// # Returns
+    ///
+    /// The matrix with the amount of questions in which each pair of tags appeared together,
+    /// or None if the site does not count them.
+    pub fn get_tag_pairs(&self) -> Option<&CoOccurrenceMatrix> {
+        self.tag_pairs.as_ref()
+    }
+
+    /// Returns the amount of pairs of tags that appeared together in the questions of the site,
+    /// taken from its pairs of tags when it counts them.
+    ///
+    /// # Returns
+    ///
+    /// The amount of pairs of tags.
+    pub fn get_co_occurrences(&self) -> u64 {
+        match &self.tag_pairs {
+            Some(tag_pairs) => tag_pairs.pairs(),
+            None => self.co_occurrences,
+        }
+    }
+
     /// Calculates the ratio between the amount of words and the amount of questions in the site.
     ///
     /// # Returns
     ///
-    /// The ratio between the amount of words and the amount of questions in the site.
-    pub fn calculate_words_questions_ratio(&self) -> f64 {
-        self.word_count as f64 / self.question_count as f64
+    /// The ratio of words/questions, or None if the site has no questions.
+    pub fn calculate_words_questions_ratio(&self) -> Option<f64> {
+        words_questions_ratio(self.word_count, self.question_count)
+    }
+
+    /// Returns whether the site has no questions, for example because its file was empty
+    /// or all its lines were malformed.
+    ///
+    /// # Returns
+    ///
+    /// True if the site has no questions.
+    pub fn is_empty(&self) -> bool {
+        self.question_count == 0
+    }
+}
+
+impl Rankable for Site {
+    /// Returns the amount of questions of the site.
+    ///
+    /// # Returns
+    ///
+    /// The amount of questions.
+    fn question_count(&self) -> u64 {
+        self.question_count
+    }
+
+    /// Returns the amount of words of the questions of the site.
+    ///
+    /// # Returns
+    ///
+    /// The amount of words.
+    fn word_count(&self) -> u64 {
+        self.word_count
+    }
+
+    /// Returns the value of the provided metric for the site.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The metric to calculate.
+    ///
+    /// # Returns
+    ///
+    /// The value of the metric, or None if the site has no value for it.
+    fn metric_value(&self, metric: RankingMetric) -> Option<f64> {
+        match metric {
+            RankingMetric::Questions => Some(self.question_count as f64),
+            RankingMetric::Words => Some(self.word_count as f64),
+            RankingMetric::Ratio => self.calculate_words_questions_ratio(),
+            RankingMetric::MedianWords => self.distribution.median(),
+            RankingMetric::CoOccurrences => Some(self.get_co_occurrences() as f64),
+        }
     }
 }
 
@@ -111,12 +274,16 @@ mod tests {
         let mut site1 = Site::new(Some("site1".to_string()));
         site1.question_count = 10;
         site1.word_count = 100;
-        site1.tags.add_tags(vec!["tag1".to_string()], 10);
+        site1
+            .tags
+            .add_tags(vec!["tag1".to_string()], 10, FieldWords::default());
 
         let mut site2 = Site::new(Some("site2".to_string()));
         site2.question_count = 20;
         site2.word_count = 200;
-        site2.tags.add_tags(vec!["tag2".to_string()], 20);
+        site2
+            .tags
+            .add_tags(vec!["tag2".to_string()], 20, FieldWords::default());
 
         site1.merge(site2);
 
@@ -127,16 +294,170 @@ mod tests {
     #[test]
     fn test_add_question() {
         let mut site = Site::new(Some("site".to_string()));
-        let question = QuestionInformation {
-            words: 10,
-            tags: vec!["tag1".to_string()],
-        };
+        let question = QuestionInformation::new(10, vec!["tag1".to_string()]);
         site.add_question(question);
 
         assert_eq!(site.question_count, 1);
         assert_eq!(site.word_count, 10);
     }
 
+    #[test]
+    fn test_fields_are_reported_for_the_site_and_its_tags() {
+        let mut site = Site::new(Some("site".to_string()));
+        for (title, body) in [(4, 10), (6, 30)] {
+            site.add_question(QuestionInformation {
+                words: title + body,
+                fields: FieldWords { title, body },
+                tags: vec!["tag1".to_string()],
+            });
+        }
+        let report = site.generate_report(&Ranking::chatty(1));
+        assert_eq!(report.fields.title_words, 10);
+        assert_eq!(report.fields.title_ratio, Some(5.0));
+        assert_eq!(report.fields.body_ratio, Some(20.0));
+        assert_eq!(report.tags["tag1"].fields, report.fields);
+    }
+
+    #[test]
+    fn test_tag_pairs_are_merged() {
+        let mut site1 = Site::with_tag_pairs(Some("site1".to_string()));
+        site1.add_question(QuestionInformation::new(
+            10,
+            vec!["rust".to_string(), "rayon".to_string()],
+        ));
+        let mut site2 = Site::with_tag_pairs(Some("site2".to_string()));
+        site2.add_question(QuestionInformation::new(
+            20,
+            vec!["rayon".to_string(), "rust".to_string(), "serde".to_string()],
+        ));
+
+        site1.merge(site2);
+
+        let pairs = site1.get_tag_pairs().unwrap();
+        assert_eq!(pairs.count("rust", "rayon"), 2);
+        assert_eq!(pairs.count("serde", "rust"), 1);
+        assert_eq!(site1.metric_value(RankingMetric::CoOccurrences), Some(4.0));
+    }
+
+    #[test]
+    fn test_tag_pairs_are_only_counted_when_requested() {
+        let question = || QuestionInformation::new(10, vec!["a".to_string(), "b".to_string()]);
+        let mut site = Site::new(Some("site".to_string()));
+        site.add_question(question());
+        assert!(site.get_tag_pairs().is_none());
+        assert_eq!(site.get_co_occurrences(), 1);
+
+        let mut with_pairs = Site::with_tag_pairs(Some("site".to_string()));
+        with_pairs.add_question(question());
+        assert_eq!(with_pairs.get_co_occurrences(), 1);
+        site.merge(with_pairs);
+        assert!(site.get_tag_pairs().is_none());
+        assert_eq!(site.get_co_occurrences(), 2);
+    }
+
+    #[test]
+    fn test_repeated_tags_are_counted_once_with_and_without_tag_pairs() {
+        let question = || {
+            QuestionInformation::new(10, vec!["a".to_string(), "a".to_string(), "b".to_string()])
+        };
+        let mut site = Site::new(Some("site".to_string()));
+        site.add_question(question());
+        let mut with_pairs = Site::with_tag_pairs(Some("site".to_string()));
+        with_pairs.add_question(question());
+
+        assert_eq!(site.get_co_occurrences(), 1);
+        assert_eq!(with_pairs.get_co_occurrences(), site.get_co_occurrences());
+        let report = site.generate_report(&Ranking::chatty(1));
+        assert_eq!(report.tags["a"].questions, 1);
+        assert_eq!(report.tags["a"].words, 10);
+    }
+
+    #[test]
+    fn test_counters_go_beyond_u32() {
+        let mut site = Site::new(Some("site".to_string()));
+        site.question_count = u32::MAX as u64;
+        site.word_count = u32::MAX as u64;
+        site.add_question(QuestionInformation::new(
+            u32::MAX as u64,
+            vec!["tag1".to_string()],
+        ));
+
+        let mut other = Site::new(Some("site".to_string()));
+        other.question_count = u32::MAX as u64;
+        other.word_count = 10;
+        site.merge(other);
+
+        assert_eq!(site.question_count, 2 * u32::MAX as u64 + 1);
+        assert_eq!(site.word_count, 2 * u32::MAX as u64 + 10);
+    }
+
+    #[test]
+    fn test_counters_saturate() {
+        let mut site = Site::new(Some("site".to_string()));
+        site.question_count = u64::MAX;
+        site.word_count = u64::MAX - 1;
+        site.add_question(QuestionInformation::new(10, vec!["tag1".to_string()]));
+
+        assert_eq!(site.question_count, u64::MAX);
+        assert_eq!(site.word_count, u64::MAX);
+    }
+
+    #[test]
+    fn test_ranking_metrics() {
+        let mut site = Site::new(Some("site".to_string()));
+        for (words, tags) in [
+            (10, vec!["a", "b", "c"]),
+            (30, vec!["a"]),
+            (20, vec!["b", "c"]),
+        ] {
+            site.add_question(QuestionInformation::new(
+                words,
+                tags.into_iter().map(String::from).collect(),
+            ));
+        }
+        assert_eq!(site.metric_value(RankingMetric::Questions), Some(3.0));
+        assert_eq!(site.metric_value(RankingMetric::Ratio), Some(20.0));
+        assert_eq!(site.metric_value(RankingMetric::MedianWords), Some(20.0));
+        assert_eq!(site.metric_value(RankingMetric::CoOccurrences), Some(4.0));
+    }
+
+    #[test]
+    fn test_chatty_tags_are_pulled_toward_the_site_ratio() {
+        let mut site = Site::new(Some("site".to_string()));
+        site.add_question(QuestionInformation::new(200, vec!["long".to_string()]));
+        for _ in 0..20 {
+            for (words, tag) in [(80, "steady"), (10, "short")] {
+                site.add_question(QuestionInformation::new(words, vec![tag.to_string()]));
+            }
+        }
+        assert_eq!(
+            site.generate_report(&Ranking::chatty(1)).chatty_tags,
+            vec!["long"]
+        );
+        assert_eq!(
+            site.generate_report(&Ranking::chatty(1).prior_weight(10.0))
+                .chatty_tags,
+            vec!["steady"]
+        );
+        assert_eq!(
+            site.generate_report(&Ranking::chatty(1).min_questions(2))
+                .chatty_tags,
+            vec!["steady"]
+        );
+    }
+
+    #[test]
+    fn test_empty_sites_have_no_ratio() {
+        let site = Site::new(Some("site".to_string()));
+        assert!(site.is_empty());
+        assert_eq!(site.calculate_words_questions_ratio(), None);
+        assert_eq!(site.metric_value(RankingMetric::MedianWords), None);
+        assert!(site
+            .generate_report(&Ranking::chatty(10))
+            .chatty_tags
+            .is_empty());
+    }
+
     #[test]
     fn test_get_name() {
         let site = Site::new(Some("site".to_string()));
@@ -144,18 +465,16 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_json() {
+    fn test_generate_report() {
         let mut site = Site::new(Some("site".to_string()));
-        let question = QuestionInformation {
-            words: 10,
-            tags: vec!["tag1".to_string()],
-        };
+        let question = QuestionInformation::new(10, vec!["tag1".to_string()]);
         site.add_question(question);
 
-        let site_data = site.generate_json();
-        assert_eq!(site_data["questions"], Value::from(1));
-        assert_eq!(site_data["words"], Value::from(10));
-        assert_eq!(site_data["tags"]["tag1"]["questions"], Value::from(1));
-        assert_eq!(site_data["tags"]["tag1"]["words"], Value::from(10));
+        let report = site.generate_report(&Ranking::chatty(10));
+        assert_eq!(report.questions, 1);
+        assert_eq!(report.words, 10);
+        assert_eq!(report.tags["tag1"].questions, 1);
+        assert_eq!(report.tags["tag1"].words, 10);
+        assert_eq!(report.chatty_tags, vec!["tag1".to_string()]);
     }
 }
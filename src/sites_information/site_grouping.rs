@@ -0,0 +1,345 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+    sync::Arc,
+};
+
+use regex::Regex;
+
+use super::{
+    diagnostics::Diagnostics,
+    parsing_error::ParsingError,
+    site::Site,
+    site_id::{DuplicateSites, SiteId},
+};
+
+/// Name of the capture group of a grouping pattern that holds the name of the site.
+const SITE_GROUP: &str = "site";
+
+/// Enum that represents how the files are assigned to the sites they belong to.
+/// The files that the rule does not assign belong to the site named after them, as described
+/// in `SiteId`.
+///
+/// # Variants
+///
+/// * `ByName` - Every file belongs to the site named after it.
+/// * `Pattern` - The files whose name matches the regular expression belong to the site matched by
+///   its `site` group, or by its first group if it has no `site` group, such as `(.*)\.part\d+`.
+/// * `Mapping` - The files listed in the mapping belong to the site they are mapped to. The files are
+///   listed by path or by file name.
+#[derive(Debug, Clone, Default)]
+pub enum SiteGrouping {
+    #[default]
+    ByName,
+    Pattern(Regex),
+    Mapping(HashMap<String, String>),
+}
+
+impl SiteGrouping {
+    /// Creates a grouping that assigns the files whose name matches the provided regular expression.
+    /// If the regular expression is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The regular expression.
+    ///
+    /// # Returns
+    ///
+    /// A result with the grouping or a ParsingError.
+    pub fn pattern(pattern: &str) -> Result<Self, ParsingError> {
+        match Regex::new(pattern) {
+            Ok(regex) => Ok(SiteGrouping::Pattern(regex)),
+            Err(error) => Err(ParsingError::ErrorInvalidPattern(
+                pattern.to_string(),
+                error.to_string(),
+            )),
+        }
+    }
+
+    /// Creates a grouping from a mapping file: a JSON object whose keys are the paths or names of
+    /// the files and whose values are the names of their sites, such as
+    /// `{"site.part1.jsonl": "site", "site.part2.jsonl": "site"}`.
+    /// If the file can not be read or it is invalid, it returns an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the mapping file.
+    ///
+    /// # Returns
+    ///
+    /// A result with the grouping or a ParsingError.
+    pub fn from_mapping_file(path: &Path) -> Result<Self, ParsingError> {
+        let invalid = |error: String| {
+            ParsingError::ErrorInvalidSiteMapping(path.display().to_string(), error)
+        };
+        let contents = fs::read_to_string(path).map_err(|error| invalid(error.to_string()))?;
+        let mapping =
+            serde_json::from_str(&contents).map_err(|error| invalid(error.to_string()))?;
+        Ok(SiteGrouping::Mapping(mapping))
+    }
+
+    /// Returns the site of the file in the provided path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// The id of the site and whether the file was assigned to it by the rule.
+    pub fn site_of(&self, path: &Path) -> (SiteId, bool) {
+        let assigned = match self {
+            SiteGrouping::ByName => None,
+            SiteGrouping::Pattern(regex) => path
+                .file_name()
+                .and_then(|file_name| {
+                    let file_name = file_name.to_string_lossy();
+                    let captures = regex.captures(&file_name)?;
+                    captures
+                        .name(SITE_GROUP)
+                        .or_else(|| captures.get(1))
+                        .map(|site| site.as_str().to_string())
+                })
+                .filter(|site| !site.is_empty()),
+            SiteGrouping::Mapping(mapping) => mapping
+                .get(&path.display().to_string())
+                .or_else(|| {
+                    let file_name = path.file_name()?.to_string_lossy();
+                    mapping.get(file_name.as_ref())
+                })
+                .cloned(),
+        };
+        match assigned {
+            Some(site) => (SiteId::from_name(&site), true),
+            None => {
+                let site = SiteId::from_path(path)
+                    .unwrap_or_else(|| SiteId::from_name(&path.display().to_string()));
+                (site, false)
+            }
+        }
+    }
+}
+
+/// Represents a site being grouped from its files.
+///
+/// # Attributes
+///
+/// * `site` - The merged information of the files, shared with the state of the file if it has one.
+/// * `files` - The paths of the files, sorted.
+/// * `assigned` - Whether every file was assigned to the site by the grouping rule.
+#[derive(Debug)]
+struct SiteGroup {
+    site: Arc<Site>,
+    files: Vec<String>,
+    assigned: bool,
+}
+
+/// Represents the sites grouped from the files read, by name. The groups of different parts of
+/// the files can be merged in any order, so they are combined in the reduce phase of the loading.
+#[derive(Debug, Default)]
+pub struct SiteGroups {
+    groups: BTreeMap<String, SiteGroup>,
+}
+
+impl SiteGroups {
+    /// Creates an empty set of groups.
+    ///
+    /// # Returns
+    ///
+    /// A new SiteGroups instance.
+    pub fn new() -> Self {
+        SiteGroups::default()
+    }
+
+    /// Adds the site read from a file to the group of its site.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `site_id` - The site of the file.
+    /// * `assigned` - Whether the file was assigned to the site by the grouping rule.
+    /// * `site` - The information read from the file, which is only copied if it has to be renamed
+    ///   or merged with other files.
+    pub fn add(&mut self, path: String, site_id: SiteId, assigned: bool, mut site: Arc<Site>) {
+        let name = site_id.into_name();
+        if site.get_name().as_ref() != Some(&name) {
+            Arc::make_mut(&mut site).set_name(Some(name.clone()));
+        }
+        let group = SiteGroup {
+            site,
+            files: vec![path],
+            assigned,
+        };
+        self.add_group(name, group);
+    }
+
+    /// Merges the groups with the groups of other files.
+    /// It takes ownership of the other groups, making them unusable after the merge.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other groups to merge with
+    pub fn merge(&mut self, other: Self) {
+        for (name, group) in other.groups {
+            self.add_group(name, group);
+        }
+    }
+
+    /// Adds a group, merging it with the group of the same site if there is one.
+    fn add_group(&mut self, name: String, group: SiteGroup) {
+        match self.groups.get_mut(&name) {
+            Some(existing) => {
+                Arc::make_mut(&mut existing.site).merge(Arc::unwrap_or_clone(group.site));
+                existing.files.extend(group.files);
+                existing.files.sort();
+                existing.assigned &= group.assigned;
+            }
+            None => {
+                self.groups.insert(name, group);
+            }
+        }
+    }
+
+    /// Returns the sites, sorted by name, recording in the diagnostics the sites that were stored
+    /// in more than one file. The files assigned to a site by the grouping rule are always merged;
+    /// the other sites stored in more than one file are merged or rejected according to the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `duplicates` - What is done with the sites stored in more than one file.
+    /// * `diagnostics` - The diagnostics where the merged sites are recorded.
+    ///
+    /// # Returns
+    ///
+    /// A result with a vector with a site per name, or a ParsingError.
+    pub fn into_sites(
+        self,
+        duplicates: DuplicateSites,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Vec<Arc<Site>>, ParsingError> {
+        let mut sites = Vec::with_capacity(self.groups.len());
+        for (name, group) in self.groups {
+            if group.files.len() > 1 {
+                if duplicates == DuplicateSites::Error && !group.assigned {
+                    let mut files = group.files.into_iter();
+                    return Err(ParsingError::ErrorDuplicateSite(
+                        name,
+                        files.next().unwrap_or_default(),
+                        files.next().unwrap_or_default(),
+                    ));
+                }
+                diagnostics.add_merged_site(name, group.files);
+            }
+            sites.push(group.site);
+        }
+        Ok(sites)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site_of(grouping: &SiteGrouping, path: &str) -> (String, bool) {
+        let (site, assigned) = grouping.site_of(Path::new(path));
+        (site.into_name(), assigned)
+    }
+
+    #[test]
+    fn patterns_assign_the_matching_files() {
+        let grouping = SiteGrouping::pattern(r"^(.*)\.part\d+").unwrap();
+        assert_eq!(
+            site_of(&grouping, "data/Site.part1.jsonl"),
+            ("site".to_string(), true)
+        );
+        assert_eq!(
+            site_of(&grouping, "data/other.jsonl"),
+            ("other".to_string(), false)
+        );
+
+        let named = SiteGrouping::pattern(r"^shard-\d+-(?P<site>[a-z.]+)").unwrap();
+        assert_eq!(
+            site_of(&named, "shard-07-academia.stackexchange.com.jsonl"),
+            ("academia.stackexchange.com".to_string(), true)
+        );
+        assert!(matches!(
+            SiteGrouping::pattern("(unclosed"),
+            Err(ParsingError::ErrorInvalidPattern(_, _))
+        ));
+    }
+
+    #[test]
+    fn mappings_assign_the_listed_files() {
+        let grouping = SiteGrouping::Mapping(HashMap::from([
+            ("a.jsonl".to_string(), "site".to_string()),
+            ("data/b.jsonl".to_string(), "site".to_string()),
+        ]));
+        assert_eq!(site_of(&grouping, "data/a.jsonl").0, "site");
+        assert_eq!(site_of(&grouping, "data/b.jsonl").0, "site");
+        assert_eq!(
+            site_of(&grouping, "other/b.jsonl"),
+            ("b".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn sites_of_a_single_file_are_not_copied() {
+        let site = Arc::new(Site::new(Some("site".to_string())));
+        let mut groups = SiteGroups::new();
+        groups.add(
+            "a".to_string(),
+            SiteId::from_name("site"),
+            false,
+            Arc::clone(&site),
+        );
+        let sites = groups
+            .into_sites(DuplicateSites::Error, &mut Diagnostics::new())
+            .unwrap();
+        assert!(Arc::ptr_eq(&sites[0], &site));
+    }
+
+    #[test]
+    fn only_assigned_files_are_merged_when_duplicates_are_errors() {
+        let mut assigned = SiteGroups::new();
+        assigned.add(
+            "a".to_string(),
+            SiteId::from_name("site"),
+            true,
+            Arc::new(Site::new(None)),
+        );
+        let mut other = SiteGroups::new();
+        other.add(
+            "b".to_string(),
+            SiteId::from_name("site"),
+            true,
+            Arc::new(Site::new(None)),
+        );
+        assigned.merge(other);
+        let mut diagnostics = Diagnostics::new();
+        let sites = assigned
+            .into_sites(DuplicateSites::Error, &mut diagnostics)
+            .unwrap();
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].get_name(), Some("site".to_string()));
+        assert_eq!(diagnostics.merged_sites()["site"], vec!["a", "b"]);
+
+        let mut duplicated = SiteGroups::new();
+        duplicated.add(
+            "b".to_string(),
+            SiteId::from_name("site"),
+            false,
+            Arc::new(Site::new(None)),
+        );
+        duplicated.add(
+            "a".to_string(),
+            SiteId::from_name("site"),
+            true,
+            Arc::new(Site::new(None)),
+        );
+        assert!(matches!(
+            duplicated.into_sites(DuplicateSites::Error, &mut Diagnostics::new()),
+            Err(ParsingError::ErrorDuplicateSite(site, first, _)) if site == "site" && first == "a"
+        ));
+    }
+}
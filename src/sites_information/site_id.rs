@@ -0,0 +1,266 @@
+use std::{fmt, path::Path};
+
+/// Extensions removed from the file names, in any order and ignoring the case.
+const EXTENSIONS: [&str; 7] = [".gz", ".zst", ".bz2", ".7z", ".jsonl", ".json", ".xml"];
+/// Names of the files that hold the questions of the site named after their directory.
+const CONTENT_NAMES: [&str; 2] = ["posts", "questions"];
+/// Characters that separate the name of the site from the name of its contents.
+const SEPARATORS: [char; 3] = ['-', '_', '.'];
+/// Domain of the sites of the StackExchange network.
+const STACKEXCHANGE_DOMAIN: &str = ".stackexchange.com";
+/// Prefix of the old names of the meta sites of the StackExchange network.
+const META_PREFIX: &str = "meta.";
+
+/// Represents the canonical name of a site, derived from the path of one of its files, so that
+/// the files of the same site give the same name regardless of their directory and format.
+///
+/// The name is the lowercase file name without the compression and format extensions, such as
+/// `.jsonl.gz`, and without a `Posts` or `Questions` suffix. Files named only `Posts` or `Questions`
+/// take the name of their directory. The meta sites are named `<site>.meta.stackexchange.com`,
+/// even if the file uses the old `meta.<site>.stackexchange.com` name.
+///
+/// # Attributes
+///
+/// * `name` - The canonical name of the site.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SiteId {
+    name: String,
+}
+
+impl SiteId {
+    /// Derives the name of the site of the file in the provided path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// The id of the site, or None if the path does not have a file name.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+        let stem = strip_content_suffix(strip_extensions(&file_name));
+        let name = if stem.is_empty() || CONTENT_NAMES.contains(&stem) {
+            path.parent()
+                .and_then(|parent| parent.file_name())
+                .map(|directory| {
+                    strip_extensions(&directory.to_string_lossy().to_lowercase()).to_string()
+                })
+                .filter(|directory| !directory.is_empty())
+                .unwrap_or_else(|| {
+                    if stem.is_empty() {
+                        file_name.clone()
+                    } else {
+                        stem.to_string()
+                    }
+                })
+        } else {
+            stem.to_string()
+        };
+        Some(SiteId {
+            name: canonical_meta_name(&name),
+        })
+    }
+
+    /// Derives the canonical name of a site from a name given by the user, such as the part of
+    /// a file name matched by a grouping rule. Its extensions and suffixes are removed as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the site.
+    ///
+    /// # Returns
+    ///
+    /// The id of the site.
+    pub fn from_name(name: &str) -> Self {
+        let lowercase = name.to_lowercase();
+        let stem = strip_content_suffix(strip_extensions(&lowercase));
+        let name = if stem.is_empty() { &lowercase } else { stem };
+        SiteId {
+            name: canonical_meta_name(name),
+        }
+    }
+
+    /// Returns the canonical name of the site.
+    ///
+    /// # Returns
+    ///
+    /// The name of the site.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the site is the meta site of another site.
+    ///
+    /// # Returns
+    ///
+    /// True if the site is a meta site.
+    pub fn is_meta(&self) -> bool {
+        self.name.starts_with(META_PREFIX) || self.name.contains(".meta.")
+    }
+
+    /// Consumes the id and returns the canonical name of the site.
+    ///
+    /// # Returns
+    ///
+    /// The name of the site.
+    pub fn into_name(self) -> String {
+        self.name
+    }
+}
+
+impl fmt::Display for SiteId {
+    /// Writes the canonical name of the site.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.name)
+    }
+}
+
+/// Removes the known extensions from the end of a lowercase file name.
+fn strip_extensions(name: &str) -> &str {
+    let mut name = name;
+    while let Some(stripped) = EXTENSIONS
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))
+    {
+        name = stripped;
+    }
+    name
+}
+
+/// Removes a `posts` or `questions` suffix preceded by a separator from a lowercase name.
+fn strip_content_suffix(name: &str) -> &str {
+    for content in CONTENT_NAMES {
+        if let Some(stripped) = name.strip_suffix(content) {
+            if let Some(site) = stripped.strip_suffix(SEPARATORS) {
+                if !site.is_empty() {
+                    return site;
+                }
+            }
+        }
+    }
+    name
+}
+
+/// Rewrites the old names of the meta sites, `meta.<site>.stackexchange.com`, as
+/// `<site>.meta.stackexchange.com`. Any other name is kept.
+fn canonical_meta_name(name: &str) -> String {
+    let site = name
+        .strip_prefix(META_PREFIX)
+        .and_then(|rest| rest.strip_suffix(STACKEXCHANGE_DOMAIN));
+    match site {
+        Some(site) if !site.is_empty() => format!("{}.meta{}", site, STACKEXCHANGE_DOMAIN),
+        _ => name.to_string(),
+    }
+}
+
+/// Enum that represents what is done when the files of different directories belong to the same site.
+///
+/// # Variants
+///
+/// * `Merge` - The information of the files is merged into a single site.
+/// * `Error` - The loading fails with a conflict between the files.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DuplicateSites {
+    #[default]
+    Merge,
+    Error,
+}
+
+impl DuplicateSites {
+    /// Returns the policy with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the policy: merge or error.
+    ///
+    /// # Returns
+    ///
+    /// The policy, or None if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "merge" => Some(DuplicateSites::Merge),
+            "error" => Some(DuplicateSites::Error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(path: &str) -> String {
+        SiteId::from_path(Path::new(path)).unwrap().into_name()
+    }
+
+    #[test]
+    fn extensions_are_removed() {
+        assert_eq!(
+            name("data/academia.stackexchange.com.jsonl"),
+            "academia.stackexchange.com"
+        );
+        assert_eq!(
+            name("data/Academia.StackExchange.com.jsonl.gz"),
+            "academia.stackexchange.com"
+        );
+        assert_eq!(name("data/site.7z"), "site");
+    }
+
+    #[test]
+    fn contents_take_the_name_of_their_site() {
+        assert_eq!(
+            name("dumps/academia.stackexchange.com-Posts.xml"),
+            "academia.stackexchange.com"
+        );
+        assert_eq!(
+            name("dumps/academia.stackexchange.com_questions.jsonl"),
+            "academia.stackexchange.com"
+        );
+        assert_eq!(
+            name("dumps/academia.stackexchange.com/Posts.xml"),
+            "academia.stackexchange.com"
+        );
+        assert_eq!(name("Posts.xml"), "posts");
+    }
+
+    #[test]
+    fn meta_sites_use_the_current_names() {
+        let old = SiteId::from_path(Path::new("meta.academia.stackexchange.com.jsonl")).unwrap();
+        assert_eq!(old.as_str(), "academia.meta.stackexchange.com");
+        assert!(old.is_meta());
+        assert_eq!(
+            name("academia.meta.stackexchange.com.jsonl"),
+            "academia.meta.stackexchange.com"
+        );
+        assert_eq!(
+            name("meta.stackoverflow.com.jsonl"),
+            "meta.stackoverflow.com"
+        );
+        assert!(!SiteId::from_path(Path::new("stackoverflow.com.jsonl"))
+            .unwrap()
+            .is_meta());
+    }
+
+    #[test]
+    fn names_given_by_the_user_are_canonical() {
+        assert_eq!(
+            SiteId::from_name("Meta.Academia.stackexchange.com.jsonl").as_str(),
+            "academia.meta.stackexchange.com"
+        );
+        assert_eq!(SiteId::from_name("site").as_str(), "site");
+    }
+
+    #[test]
+    fn duplicate_policies_are_selected_by_name() {
+        assert_eq!(
+            DuplicateSites::from_name("merge"),
+            Some(DuplicateSites::Merge)
+        );
+        assert_eq!(
+            DuplicateSites::from_name("error"),
+            Some(DuplicateSites::Error)
+        );
+        assert_eq!(DuplicateSites::from_name("ignore"), None);
+    }
+}
@@ -1,78 +1,220 @@
 use super::{
-    parsing_error::ParsingError, question::Question, site::Site, tags_collection::TagsCollection,
+    analysis_state::{
+        file_metadata, hash_file, line_starts_at, AnalysisState, FileChange, FileFingerprint,
+        FileState, IncrementalLoad,
+    },
+    co_occurrence_matrix::CoOccurrenceMatrix,
+    compression::{Compression, FileFormat},
+    diagnostics::{Diagnostics, FileDiagnostics},
+    input_schema::InputSchema,
+    input_selection::InputSelection,
+    line_reader::{read_site, read_site_from, ReadStrategy},
+    parsing_error::ParsingError,
+    question_filter::QuestionFilter,
+    question_parser::QuestionParser,
+    ranking::{words_questions_ratio, Rankable, Ranking},
+    report::{LeaderboardReport, RelatedTagsReport, Report, SiteReport, TotalsReport},
+    site::Site,
+    site_grouping::{SiteGrouping, SiteGroups},
+    site_id::{DuplicateSites, SiteId},
+    tags_collection::TagsCollection,
+    text_layout::TextLayout,
+    tokenizer::Tokenizer,
 };
 
 use std::{
-    fs::{read_dir, File, ReadDir},
-    io::{BufRead, BufReader},
-    path::PathBuf,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use serde_json::Value;
-
-use rayon::{prelude::*, ThreadPoolBuilder};
-
-const JSONL_EXTENSION: &str = "jsonl";
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
 
 /// Represents a collection of sites.
+/// The parallel work of the collection runs in its own thread pool, if it has one,
+/// or in the rayon pool of the caller otherwise.
 #[derive(Debug)]
 pub struct SitesCollection {
-    sites: Option<Vec<Site>>,
+    sites: Option<Vec<Arc<Site>>>,
+    strict: bool,
+    strategy: ReadStrategy,
+    parser: QuestionParser,
+    grouping: SiteGrouping,
+    duplicates: DuplicateSites,
+    pool: Option<ThreadPool>,
 }
 
 impl SitesCollection {
-    /// Creates a new SitesCollection instance.
+    /// Creates a new SitesCollection instance without its own thread pool.
     ///
     /// # Returns
     ///
     /// A new SitesCollection instance.
     pub fn new() -> Self {
-        SitesCollection { sites: None }
+        SitesCollection {
+            sites: None,
+            strict: false,
+            strategy: ReadStrategy::default(),
+            parser: QuestionParser::default(),
+            grouping: SiteGrouping::default(),
+            duplicates: DuplicateSites::default(),
+            pool: None,
+        }
+    }
+
+    /// Creates a builder to configure a new SitesCollection instance.
+    ///
+    /// # Returns
+    ///
+    /// A new SitesCollectionBuilder instance.
+    pub fn builder() -> SitesCollectionBuilder {
+        SitesCollectionBuilder::new()
+    }
+
+    /// Sets whether the loading of the sites should fail on the first invalid line or file.
+    /// When it is not strict, invalid lines and files are skipped and reported in the diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether the loading should fail on the first problem.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Sets how the lines of each file are distributed among the threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy used to read the files.
+    pub fn set_read_strategy(&mut self, strategy: ReadStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Sets the rule that assigns the files to the sites they belong to, such as the shards of a site.
+    ///
+    /// # Arguments
+    ///
+    /// * `grouping` - The rule that assigns the files to their sites.
+    pub fn set_site_grouping(&mut self, grouping: SiteGrouping) {
+        self.grouping = grouping;
+    }
+
+    /// Sets what is done with the sites stored in more than one file, such as the same dump in
+    /// two directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `duplicates` - Whether the sites are merged or the loading fails.
+    pub fn set_duplicate_sites(&mut self, duplicates: DuplicateSites) {
+        self.duplicates = duplicates;
+    }
+
+    /// Sets the shape of the JSON questions of the lines of the files.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the questions.
+    pub fn set_input_schema(&mut self, schema: InputSchema) {
+        self.parser.set_schema(schema);
+    }
+
+    /// Sets the field held by each of the texts of the questions, such as the title or the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout of the texts of the questions.
+    pub fn set_text_layout(&mut self, layout: TextLayout) {
+        self.parser.set_layout(layout);
+    }
+
+    /// Sets the tokenizer that counts the words of the texts of the questions.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokenizer` - The tokenizer.
+    pub fn set_tokenizer(&mut self, tokenizer: Box<dyn Tokenizer>) {
+        self.parser.set_tokenizer(tokenizer);
+    }
+
+    /// Sets the filter the questions must pass to be aggregated. The sites keep the questions that
+    /// pass it, and the sites of the files without any are reported as empty. The files of the
+    /// sites rejected by its `site` predicates are not read, so those sites are not reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter of the questions, or None to aggregate every question.
+    pub fn set_question_filter(&mut self, filter: Option<QuestionFilter>) {
+        self.parser.set_filter(filter);
+    }
+
+    /// Sets whether the sites count the questions in which each pair of tags appeared, which is
+    /// needed by the related tags report.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_pairs` - Whether the pairs of tags are counted.
+    pub fn set_tag_pairs(&mut self, tag_pairs: bool) {
+        self.parser.set_tag_pairs(tag_pairs);
     }
 
-    /// Sets the amount of threads to be used by the program.
+    /// Sets the amount of threads used by the collection, replacing its thread pool.
+    /// If the amount is 0, rayon decides the amount of threads.
     /// If there was an error while building the threadpool, it returns an error.
     ///
     /// # Arguments
     ///
-    /// * `num_threads` - The number of threads to be used by the program.
+    /// * `num_threads` - The number of threads to be used by the collection.
     ///
     /// # Returns
     ///
     /// A result that could carry a ParsingError.
-    pub fn set_thread_amount(&self, num_threads: u8) -> Result<(), ParsingError> {
-        if let Err(error) = ThreadPoolBuilder::new()
-            .num_threads(num_threads as usize)
-            .build_global()
-        {
-            return Err(ParsingError::ErrorWhileBuildingThreadpool(
-                error.to_string(),
-            ));
-        }
+    pub fn set_thread_amount(&mut self, num_threads: u8) -> Result<(), ParsingError> {
+        self.pool = Some(build_thread_pool(num_threads)?);
         Ok(())
     }
 
-    /// Loads the sites with json extension from the provided path using the provided amount of threads.
-    /// The function returns an error if the path is invalid, if the directory is empty or if there was an error while reading the files.
+    /// Returns the amount of threads used by the collection.
+    ///
+    /// # Returns
+    ///
+    /// The amount of threads of the collection's pool, or of the caller's pool if it has none.
+    pub fn thread_amount(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    /// Runs the provided operation inside the thread pool of the collection, if it has one.
     ///
     /// # Arguments
     ///
-    /// * `files_path` - The path where the files are located.
+    /// * `operation` - The operation to run.
     ///
     /// # Returns
     ///
-    /// A result that could carry a ParsingError.
-    pub fn load_sites(&mut self, files_path: &str) -> Result<(), ParsingError> {
-        let data_directory = Self::get_directory(files_path)?;
-        let files_paths = Self::get_files_paths(data_directory)?;
-        self.sites = Some(Self::get_sites(files_paths));
-        Ok(())
+    /// The result of the operation.
+    fn install<R, F>(&self, operation: F) -> R
+    where
+        R: Send,
+        F: FnOnce() -> R + Send,
+    {
+        match &self.pool {
+            Some(pool) => pool.install(operation),
+            None => operation(),
+        }
     }
 
-    /**
-     * Returns the directory from the provided path. If the directory does not exist, it returns an error.
-     */
-    /// Returns the directory from the provided path. If the directory does not exist, it returns an error.
+    /// Loads the sites with jsonl extension from the provided path using the provided amount of threads.
+    /// The `Posts.xml` files of the official StackExchange data dump (`.xml`) are read as well.
+    /// Files compressed with gzip (`.gz`) are decompressed while they are read, as well as
+    /// zstd (`.zst`) and bzip2 (`.bz2`) files when the matching feature is enabled.
+    /// The function returns an error if the path is invalid or if the directory is empty.
+    /// Lines and files that could not be used are skipped and reported in the returned diagnostics,
+    /// unless the collection is in strict mode, in which case the first of them is returned as an error.
+    /// The files are assigned to their sites by the grouping rule, or named after them as described
+    /// in `SiteId`, and the files of the same site are merged. A site stored in more than one file
+    /// that the rule did not assign is merged or rejected according to the policy for duplicate sites.
     ///
     /// # Arguments
     ///
@@ -80,104 +222,401 @@ impl SitesCollection {
     ///
     /// # Returns
     ///
-    /// A result that could carry a ReadDir or a ParsingError.
-    fn get_directory(files_path: &str) -> Result<ReadDir, ParsingError> {
-        match read_dir(files_path) {
-            Ok(directory) => Ok(directory),
-            Err(_) => Err(ParsingError::ErrorCouldNotOpenDataDirectory),
+    /// A result with the diagnostics of the loading or a ParsingError.
+    pub fn load_sites(&mut self, files_path: &str) -> Result<Diagnostics, ParsingError> {
+        self.load_sites_from(&InputSelection::new().directory(files_path))
+    }
+
+    /// Loads the sites from the files of the provided selection, in the same way as `load_sites`.
+    /// The function returns an error if a directory is invalid or if no files are selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The directories, files and patterns that select the files to load.
+    ///
+    /// # Returns
+    ///
+    /// A result with the diagnostics of the loading or a ParsingError.
+    pub fn load_sites_from(
+        &mut self,
+        selection: &InputSelection,
+    ) -> Result<Diagnostics, ParsingError> {
+        let files_paths = selection.files_paths()?;
+        let strict = self.strict;
+        let strategy = self.strategy;
+        let parser = &self.parser;
+        let grouping = &self.grouping;
+        let duplicates = self.duplicates;
+        let (sites, diagnostics) = self.install(|| {
+            Self::get_sites(files_paths, strict, strategy, parser, grouping, duplicates)
+        })?;
+        self.sites = Some(sites);
+        Ok(diagnostics)
+    }
+
+    /// Loads the sites from the files of the provided selection reusing the state of a previous
+    /// analysis: the files that did not change are not read again, only the lines appended to a
+    /// file since the previous analysis are read, and the new or rewritten files are read entirely.
+    /// If there is no previous state, or it was generated with another schema, layout, tokenizer,
+    /// filter expression or counting of tag pairs, every file is read.
+    /// The strict mode also applies to the problems stored in the reused state.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The directories, files and patterns that select the files to load.
+    /// * `previous` - The state of the previous analysis, if there is one.
+    ///
+    /// # Returns
+    ///
+    /// A result with the diagnostics of the loading and the updated state, or a ParsingError.
+    pub fn load_sites_incrementally(
+        &mut self,
+        selection: &InputSelection,
+        previous: Option<&AnalysisState>,
+    ) -> Result<IncrementalLoad, ParsingError> {
+        let files_paths = selection.files_paths()?;
+        let strict = self.strict;
+        let strategy = self.strategy;
+        let parser = &self.parser;
+        let grouping = &self.grouping;
+        let previous = previous.filter(|previous| previous.is_compatible(parser));
+        let filters_sites = parser.filter().is_some_and(QuestionFilter::uses_sites);
+        let results: Vec<(SiteId, bool, String, FileState, FileChange)> =
+            self.install(|| {
+                files_paths
+                    .par_iter()
+                    .filter_map(|path| {
+                        let (site_id, assigned) = grouping.site_of(path);
+                        if parser.excludes_site(Some(site_id.as_str())) {
+                            return None;
+                        }
+                        let key = path.display().to_string();
+                        let site_name = Some(site_id.as_str().to_string());
+                        // The questions of a file filtered by site depend on the site it belongs to.
+                        let stored = previous
+                            .and_then(|previous| previous.files.get(&key))
+                            .filter(|stored| {
+                                !filters_sites
+                                    || stored.site.as_ref().map(|site| site.get_name())
+                                        == Some(site_name.clone())
+                            });
+                        let update =
+                            Self::update_file(path, site_name, stored, strict, strategy, parser);
+                        Some(update.map(|(file_state, change)| {
+                            (site_id, assigned, key, file_state, change)
+                        }))
+                    })
+                    .collect::<Result<_, ParsingError>>()
+            })?;
+
+        let mut load = IncrementalLoad {
+            diagnostics: Diagnostics::new(),
+            state: AnalysisState::new(parser),
+            unchanged: 0,
+            appended: 0,
+            processed: 0,
+        };
+        let mut groups = SiteGroups::new();
+        for (site_id, assigned, key, file_state, change) in results {
+            match change {
+                FileChange::Unchanged => load.unchanged += 1,
+                FileChange::Appended => load.appended += 1,
+                FileChange::Processed => load.processed += 1,
+            }
+            // The sites are shared with the state, and only the diagnostics with problems are kept.
+            if let Some(site) = &file_state.site {
+                groups.add(key.clone(), site_id, assigned, Arc::clone(site));
+            }
+            if file_state.diagnostics.has_problems() {
+                load.diagnostics.add_file(file_state.diagnostics.clone());
+            }
+            load.state.files.insert(key, file_state);
         }
+        self.sites = Some(groups.into_sites(self.duplicates, &mut load.diagnostics)?);
+        Ok(load)
     }
 
-    /// Returns the paths of the files with json extension from the provided directory.
-    /// If no files are found, it returns an error.
+    /// Returns the updated state of a file, reusing its stored state when the file did not change
+    /// and reading only the appended lines when the stored contents are a prefix of the file.
     ///
     /// # Arguments
     ///
-    /// * `directory` - The directory where the files are located.
+    /// * `path` - The path of the file.
+    /// * `site_name` - The name of the site the file belongs to.
+    /// * `stored` - The state of the file in the previous analysis, if it can be reused.
+    /// * `strict` - Whether the first invalid line or file should be returned as an error.
+    /// * `strategy` - How the lines of the file are distributed among the threads.
+    /// * `parser` - The parser of the questions of the lines.
     ///
     /// # Returns
     ///
-    /// A result that could carry a vector of PathBuf or a ParsingError.
-    fn get_files_paths(directory: ReadDir) -> Result<Vec<PathBuf>, ParsingError> {
-        let dir_entries: Vec<PathBuf> = directory
-            .filter_map(|entry| {
-                let valid_entry = match entry {
-                    Ok(valid_entry) => valid_entry,
-                    Err(_) => return None,
+    /// A result with the state of the file and what was done with it, or a ParsingError.
+    fn update_file(
+        path: &Path,
+        site_name: Option<String>,
+        stored: Option<&FileState>,
+        strict: bool,
+        strategy: ReadStrategy,
+        parser: &QuestionParser,
+    ) -> Result<(FileState, FileChange), ParsingError> {
+        let file_path = path.display().to_string();
+        let previous = stored.and_then(|stored| {
+            let fingerprint = stored.fingerprint?;
+            stored.site.as_ref()?;
+            Some((stored, fingerprint))
+        });
+        if let Some((stored, previous)) = previous {
+            let metadata = file_metadata(path).ok();
+            if previous.modified.is_some() && metadata == Some((previous.size, previous.modified)) {
+                Self::check_strict(&file_path, &stored.diagnostics, strict)?;
+                return Ok((stored.clone(), FileChange::Unchanged));
+            }
+        }
+
+        let fingerprint = match FileFingerprint::of(path) {
+            Ok(fingerprint) => fingerprint,
+            Err(error) if strict => {
+                return Err(ParsingError::ErrorCouldNotReadFile(
+                    file_path,
+                    error.to_string(),
+                ))
+            }
+            Err(error) => {
+                let file_state = FileState {
+                    fingerprint: None,
+                    lines: 0,
+                    diagnostics: FileDiagnostics::unreadable(file_path, error.to_string()),
+                    site: None,
                 };
-                let path = valid_entry.path();
-                if let Some(extension) = path.extension() {
-                    if extension == JSONL_EXTENSION {
-                        return Some(path);
-                    }
+                return Ok((file_state, FileChange::Processed));
+            }
+        };
+
+        if let Some((stored, previous)) = previous {
+            if previous.size == fingerprint.size && previous.hash == fingerprint.hash {
+                Self::check_strict(&file_path, &stored.diagnostics, strict)?;
+                let file_state = FileState {
+                    fingerprint: Some(fingerprint),
+                    ..stored.clone()
+                };
+                return Ok((file_state, FileChange::Unchanged));
+            }
+            if Self::was_appended(path, &previous, &fingerprint) {
+                if let Some(file_state) = Self::read_appended(
+                    path,
+                    site_name.clone(),
+                    stored,
+                    fingerprint,
+                    strategy,
+                    strict,
+                    parser,
+                ) {
+                    Self::check_strict(&file_path, &file_state.diagnostics, strict)?;
+                    return Ok((file_state, FileChange::Appended));
                 }
-                None
-            })
-            .collect();
-        if dir_entries.is_empty() {
-            return Err(ParsingError::ErrorWhileBrowsingTheDirectoryEntries(
-                "No files found".to_string(),
-            ));
+            }
+        }
+
+        let (site, diagnostics, lines) = Self::get_site(path, site_name, strict, strategy, parser)?;
+        let file_state = FileState {
+            fingerprint: site.as_ref().map(|_| fingerprint),
+            lines,
+            diagnostics,
+            site: site.map(Arc::new),
+        };
+        Ok((file_state, FileChange::Processed))
+    }
+
+    /// Returns whether the only change of a plain jsonl file since it was read is that lines were
+    /// appended to it: it grew, its previous size ends a line and its first bytes did not change.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `previous` - The fingerprint of the file when it was read.
+    /// * `current` - The current fingerprint of the file.
+    ///
+    /// # Returns
+    ///
+    /// True if only the bytes after the previous size have to be read.
+    fn was_appended(path: &Path, previous: &FileFingerprint, current: &FileFingerprint) -> bool {
+        previous.size > 0
+            && previous.size < current.size
+            && Compression::from_path(path) == Some(Compression::Plain)
+            && FileFormat::from_path(path) == Some(FileFormat::JsonLines)
+            && line_starts_at(path, previous.size).unwrap_or(false)
+            && hash_file(path, Some(previous.size)).ok() == Some(previous.hash)
+    }
+
+    /// Reads the lines appended to a file and merges them with its stored state.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `site_name` - The name of the site the file belongs to.
+    /// * `stored` - The state of the file in the previous analysis.
+    /// * `fingerprint` - The current fingerprint of the file.
+    /// * `strategy` - How the lines of the file are distributed among the threads.
+    /// * `strict` - Whether the reading stops at the first invalid line.
+    /// * `parser` - The parser of the questions of the lines.
+    ///
+    /// # Returns
+    ///
+    /// The updated state of the file, or None if the appended lines could not be read.
+    fn read_appended(
+        path: &Path,
+        site_name: Option<String>,
+        stored: &FileState,
+        fingerprint: FileFingerprint,
+        strategy: ReadStrategy,
+        strict: bool,
+        parser: &QuestionParser,
+    ) -> Option<FileState> {
+        let previous = stored.fingerprint?;
+        let (appended, mut diagnostics, lines) =
+            read_site_from(path, site_name, previous.size, strategy, strict, parser).ok()?;
+        diagnostics.offset_lines(stored.lines);
+
+        let mut file_state = stored.clone();
+        if let Some(site) = &mut file_state.site {
+            Arc::make_mut(site).merge(appended);
         }
-        Ok(dir_entries)
+        file_state.diagnostics.merge(diagnostics);
+        file_state.lines += lines;
+        file_state.fingerprint = Some(fingerprint);
+        Some(file_state)
     }
 
-    /// Returns the sites from the provided files paths using the provided amount of threads.
+    /// Returns the sites from the provided files paths using the provided amount of threads,
+    /// together with the diagnostics of the files that had problems. The files of the same site
+    /// are combined while the partial results of the threads are reduced.
     ///
     /// # Arguments
     ///
     /// * `files_paths` - The paths of the files to read.
+    /// * `strict` - Whether the first invalid line or file should be returned as an error.
+    /// * `strategy` - How the lines of the files are distributed among the threads.
+    /// * `parser` - The parser of the questions of the lines.
+    /// * `grouping` - The rule that assigns the files to their sites.
+    /// * `duplicates` - What is done with the sites stored in more than one file.
     ///
     /// # Returns
     ///
-    /// A vector with the sites.
-    fn get_sites(files_paths: Vec<PathBuf>) -> Vec<Site> {
-        let sites: Vec<Site> = files_paths
+    /// A result with a vector with the sites and the diagnostics, or a ParsingError.
+    fn get_sites(
+        files_paths: Vec<PathBuf>,
+        strict: bool,
+        strategy: ReadStrategy,
+        parser: &QuestionParser,
+        grouping: &SiteGrouping,
+        duplicates: DuplicateSites,
+    ) -> Result<(Vec<Arc<Site>>, Diagnostics), ParsingError> {
+        let empty = || (SiteGroups::new(), Diagnostics::new());
+        let (groups, mut diagnostics) = files_paths
             .par_iter()
             .filter_map(|path| {
-                if let Ok(file) = File::open(path) {
-                    let file_name = path
-                        .file_name()
-                        .map(|name| name.to_string_lossy().into_owned());
-                    let reader = BufReader::new(file);
-                    let site = reader
-                        .lines()
-                        .par_bridge()
-                        .filter_map(|line| {
-                            let valid_line = match line {
-                                Ok(line) => line,
-                                Err(_) => return None,
-                            };
-                            match serde_json::from_str::<Question>(&valid_line) {
-                                Ok(question) => Some(question.into_information()),
-                                Err(e) => {
-                                    eprintln!("{}", e);
-                                    None
-                                }
-                            }
-                        })
-                        .fold(
-                            || Site::new(file_name.clone()),
-                            |mut site, question| {
-                                site.add_question(question);
-                                site
-                            },
-                        )
-                        .reduce(
-                            || Site::new(file_name.clone()),
-                            |mut site, other| {
-                                site.merge(other);
-                                site
-                            },
-                        );
-                    Some(site)
-                } else {
-                    None
+                let (site_id, assigned) = grouping.site_of(path);
+                if parser.excludes_site(Some(site_id.as_str())) {
+                    return None;
                 }
+                let site_name = Some(site_id.as_str().to_string());
+                let read = Self::get_site(path, site_name, strict, strategy, parser);
+                Some(read.map(|read| (path, site_id, assigned, read)))
             })
-            .collect();
+            .try_fold(
+                empty,
+                |(mut groups, mut diagnostics), read: Result<_, ParsingError>| {
+                    let (path, site_id, assigned, (site, file_diagnostics, _)) = read?;
+                    if let Some(site) = site {
+                        let path = path.display().to_string();
+                        groups.add(path, site_id, assigned, Arc::new(site));
+                    }
+                    diagnostics.add_file(file_diagnostics);
+                    Ok((groups, diagnostics))
+                },
+            )
+            .try_reduce(
+                empty,
+                |(mut groups, mut diagnostics), (other_groups, other_diagnostics)| {
+                    groups.merge(other_groups);
+                    diagnostics.merge(other_diagnostics);
+                    Ok((groups, diagnostics))
+                },
+            )?;
+        let sites = groups.into_sites(duplicates, &mut diagnostics)?;
+        Ok((sites, diagnostics))
+    }
+
+    /// Returns the site stored in the provided file, processing its lines in parallel,
+    /// together with the diagnostics of the file. If the file could not be opened, no site is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    /// * `site_name` - The name of the site the file belongs to.
+    /// * `strict` - Whether the first invalid line or file should be returned as an error.
+    /// * `strategy` - How the lines of the file are distributed among the threads.
+    /// * `parser` - The parser of the questions of the lines.
+    ///
+    /// # Returns
+    ///
+    /// A result with the site, if the file could be read, its diagnostics and its amount of lines,
+    /// or a ParsingError.
+    fn get_site(
+        path: &Path,
+        site_name: Option<String>,
+        strict: bool,
+        strategy: ReadStrategy,
+        parser: &QuestionParser,
+    ) -> Result<(Option<Site>, FileDiagnostics, u64), ParsingError> {
+        let file_path = path.display().to_string();
 
-        sites
+        let (site, file_diagnostics, lines) =
+            match read_site(path, site_name, strategy, strict, parser) {
+                Ok(result) => result,
+                Err(error) if strict => {
+                    return Err(ParsingError::ErrorCouldNotReadFile(
+                        file_path,
+                        error.to_string(),
+                    ))
+                }
+                Err(error) => {
+                    return Ok((
+                        None,
+                        FileDiagnostics::unreadable(file_path, error.to_string()),
+                        0,
+                    ))
+                }
+            };
+
+        Self::check_strict(&file_path, &file_diagnostics, strict)?;
+        Ok((Some(site), file_diagnostics, lines))
+    }
+
+    /// Returns the first invalid line of a file as an error, if the collection is in strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path of the file.
+    /// * `diagnostics` - The diagnostics of the file.
+    /// * `strict` - Whether the first invalid line should be returned as an error.
+    ///
+    /// # Returns
+    ///
+    /// A result that could carry a ParsingError.
+    fn check_strict(
+        file_path: &str,
+        diagnostics: &FileDiagnostics,
+        strict: bool,
+    ) -> Result<(), ParsingError> {
+        match diagnostics.errors.first() {
+            Some(error) if strict => Err(ParsingError::ErrorInvalidLine(
+                file_path.to_string(),
+                error.line,
+                error.message.clone(),
+            )),
+            _ => Ok(()),
+        }
     }
 
     /// Generates a collection with all the tags from the different sites.
@@ -185,42 +624,142 @@ impl SitesCollection {
     /// # Returns
     ///
     /// A collection with all the tags from the different sites.
-    fn get_all_tags(&self) -> TagsCollection {
+    pub fn get_all_tags(&self) -> TagsCollection {
         match &self.sites {
-            Some(sites) => sites
-                .par_iter()
-                .fold(TagsCollection::new, |mut tags, site| {
-                    tags.merge_ref(site.get_tags());
-                    tags
-                })
-                .reduce(TagsCollection::new, |mut tags, other| {
-                    tags.merge(other);
-                    tags
-                }),
+            Some(sites) => self.install(|| {
+                sites
+                    .par_iter()
+                    .fold(TagsCollection::new, |mut tags, site| {
+                        tags.merge_ref(site.get_tags());
+                        tags
+                    })
+                    .reduce(TagsCollection::new, |mut tags, other| {
+                        tags.merge(other);
+                        tags
+                    })
+            }),
             None => TagsCollection::new(),
         }
     }
 
-    /// Generates a json with the sites information.
+    /// Generates a matrix with the pairs of tags of all the sites.
+    /// The pairs are only counted if the collection was configured to count them.
+    ///
+    /// # Returns
+    ///
+    /// A matrix with the amount of questions of all the sites in which each pair of tags appeared.
+    pub fn get_all_co_occurrences(&self) -> CoOccurrenceMatrix {
+        match &self.sites {
+            Some(sites) => self.install(|| {
+                sites
+                    .par_iter()
+                    .fold(CoOccurrenceMatrix::new, |mut matrix, site| {
+                        if let Some(tag_pairs) = site.get_tag_pairs() {
+                            matrix.merge_ref(tag_pairs);
+                        }
+                        matrix
+                    })
+                    .reduce(CoOccurrenceMatrix::new, |mut matrix, other| {
+                        matrix.merge(other);
+                        matrix
+                    })
+            }),
+            None => CoOccurrenceMatrix::new(),
+        }
+    }
+
+    /// Generates the tags that appeared together the most with each tag, for each site
+    /// and for all the sites together. The sites only have related tags if the collection was
+    /// configured to count the pairs of tags.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The maximum amount of related tags of each tag.
     ///
     /// # Returns
     ///
-    /// A json with the sites information.
-    pub fn generate_sites_jason(&self) -> Value {
-        let mut sites_data = Value::Object(serde_json::Map::new());
+    /// The related tags of each tag, by site and for all the sites.
+    pub fn generate_related_tags_report(&self, amount: usize) -> RelatedTagsReport {
+        let mut sites_data = BTreeMap::new();
         if let Some(sites) = &self.sites {
             for (index, site) in sites.iter().enumerate() {
                 let name = match site.get_name() {
                     Some(name) => name,
                     None => index.to_string(),
                 };
-                sites_data[name] = site.generate_json();
+                let related_tags = site
+                    .get_tag_pairs()
+                    .map(|tag_pairs| tag_pairs.generate_report(amount))
+                    .unwrap_or_default();
+                sites_data.insert(name, related_tags);
+            }
+        }
+        RelatedTagsReport {
+            sites: sites_data,
+            tags: self.get_all_co_occurrences().generate_report(amount),
+        }
+    }
+
+    /// Generates the report of each site.
+    ///
+    /// # Arguments
+    ///
+    /// * `chatty_tags` - The ranking of the chatty tags included for each site.
+    ///
+    /// # Returns
+    ///
+    /// The report of each site, by name.
+    pub fn generate_sites_report(&self, chatty_tags: &Ranking) -> BTreeMap<String, SiteReport> {
+        let mut sites_data = BTreeMap::new();
+        if let Some(sites) = &self.sites {
+            for (index, site) in sites.iter().enumerate() {
+                let name = match site.get_name() {
+                    Some(name) => name,
+                    None => index.to_string(),
+                };
+                sites_data.insert(name, site.generate_report(chatty_tags));
             }
         }
         sites_data
     }
 
-    /// Returns the sites with the highest ratio of words/questions in a json format.
+    /// Calculates the ratio between the amount of words and the amount of questions of all the sites.
+    ///
+    /// # Returns
+    ///
+    /// The ratio of words/questions of all the sites together, or None if there are no questions.
+    pub fn calculate_words_questions_ratio(&self) -> Option<f64> {
+        let (questions, words) =
+            self.sites
+                .iter()
+                .flatten()
+                .fold((0u64, 0u64), |(questions, words), site| {
+                    (
+                        questions.saturating_add(site.question_count()),
+                        words.saturating_add(site.word_count()),
+                    )
+                });
+        words_questions_ratio(words, questions)
+    }
+
+    /// Returns the names of the sites without questions, which are excluded from the rankings.
+    ///
+    /// # Returns
+    ///
+    /// The names of the empty sites, sorted.
+    pub fn get_empty_sites(&self) -> Vec<String> {
+        let mut empty_sites: Vec<String> = self
+            .sites
+            .iter()
+            .flatten()
+            .filter(|site| site.is_empty())
+            .map(|site| site.get_name().unwrap_or("Archivo sin nombre".to_string()))
+            .collect();
+        empty_sites.sort();
+        empty_sites
+    }
+
+    /// Returns the sites with the highest ratio of words/questions.
     ///
     /// # Arguments
     ///
@@ -228,68 +767,150 @@ impl SitesCollection {
     ///
     /// # Returns
     ///
-    /// A json with the sites with the highest ratio of words/questions.
-    pub fn get_chatty_sites(&self, number_of_sites: u8) -> Value {
-        let ordered_sites = match &self.sites {
-            Some(sites) => {
-                let mut chatty_sites: Vec<&Site> = sites.iter().collect();
-                chatty_sites.sort_by(|a, b| {
-                    let ratio_a = a.calculate_words_questions_ratio();
-                    let ratio_b = b.calculate_words_questions_ratio();
-                    ratio_b
-                        .partial_cmp(&ratio_a)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
+    /// The names of the sites with the highest ratio of words/questions.
+    pub fn get_chatty_sites(&self, number_of_sites: u8) -> Vec<String> {
+        self.rank_sites(&Ranking::chatty(number_of_sites))
+    }
 
-                chatty_sites
-                    .into_iter()
-                    .take(number_of_sites as usize)
+    /// Returns the sites ranked by the provided ranking.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranking` - The metric, order, threshold and limit of the ranking.
+    ///
+    /// # Returns
+    ///
+    /// The names of the ranked sites.
+    pub fn rank_sites(&self, ranking: &Ranking) -> Vec<String> {
+        match &self.sites {
+            Some(sites) => {
+                let named_sites: Vec<(String, &Site)> = sites
+                    .iter()
                     .map(|site| {
-                        Value::String(site.get_name().unwrap_or("Archivo sin nombre".to_string()))
+                        let name = site.get_name().unwrap_or("Archivo sin nombre".to_string());
+                        (name, site.as_ref())
                     })
-                    .collect()
+                    .collect();
+                ranking.rank(
+                    named_sites
+                        .iter()
+                        .map(|(name, site)| (name.as_str(), *site)),
+                )
             }
             None => Vec::new(),
-        };
+        }
+    }
 
-        Value::Array(ordered_sites)
+    /// Generates a leaderboard of sites and tags for each provided ranking.
+    ///
+    /// # Arguments
+    ///
+    /// * `rankings` - The name and the ranking of each leaderboard.
+    ///
+    /// # Returns
+    ///
+    /// The leaderboards, by name.
+    pub fn generate_leaderboards(
+        &self,
+        rankings: &[(String, Ranking)],
+    ) -> BTreeMap<String, LeaderboardReport> {
+        if rankings.is_empty() {
+            return BTreeMap::new();
+        }
+        let tags = self.get_all_tags();
+        let mean = self.calculate_words_questions_ratio().unwrap_or(0.0);
+        rankings
+            .iter()
+            .map(|(name, ranking)| {
+                let leaderboard = LeaderboardReport {
+                    metric: ranking.metric,
+                    min_questions: ranking.min_questions,
+                    order: ranking.order,
+                    prior_weight: ranking.prior_weight,
+                    sites: self.rank_sites(ranking),
+                    tags: tags.rank_tags_with_mean(ranking, mean),
+                };
+                (name.clone(), leaderboard)
+            })
+            .collect()
     }
 
-    /// Generates a json with the total amount of chatty sites and chatty tags.
+    /// Generates the totals of the chatty sites and chatty tags.
+    /// If the chatty tags ranking smooths the ratio, the tags are pulled toward the ratio of all the sites.
     ///
     /// # Arguments
     ///
     /// * `tags` - The tags collection.
+    /// * `chatty_sites` - The ranking of the chatty sites.
+    /// * `chatty_tags` - The ranking of the chatty tags.
     ///
     /// # Returns
     ///
-    /// A json with the total amount of chatty sites and chatty tags.
-    pub fn generate_totals_json(&self, tags: TagsCollection) -> Value {
-        let mut data = Value::Object(serde_json::Map::new());
-        data["chatty_sites"] = self.get_chatty_sites(10);
-        data["chatty_tags"] = tags.generate_chatty_tags_json(10);
-        data
+    /// The totals of the chatty sites and chatty tags.
+    pub fn generate_totals_report(
+        &self,
+        tags: &TagsCollection,
+        chatty_sites: &Ranking,
+        chatty_tags: &Ranking,
+    ) -> TotalsReport {
+        TotalsReport {
+            chatty_sites: self.rank_sites(chatty_sites),
+            empty_sites: self.get_empty_sites(),
+            chatty_tags: tags.rank_tags_with_mean(
+                chatty_tags,
+                self.calculate_words_questions_ratio().unwrap_or(0.0),
+            ),
+        }
     }
 
-    /// Generates a json with the site collection information.
+    /// Generates the report with the site collection information, ranking the chatty sites
+    /// and tags by the highest ratio of words/questions.
     ///
     /// # Arguments
     ///
     /// * `padron` - The padron number.
+    /// * `number_of_sites` - The number of chatty sites to include in the totals.
+    /// * `number_of_tags` - The number of chatty tags to include for each site and in the totals.
     ///
     /// # Returns
     ///
-    /// A json with the site collection information.
-    pub fn generate_json_information(&self, padron: &str) -> Value {
-        let mut data = Value::Object(serde_json::Map::new());
-        data["padron"] = Value::String(padron.to_string());
-        data["sites"] = self.generate_sites_jason();
+    /// The report with the site collection information, without diagnostics nor related tags.
+    pub fn generate_report(&self, padron: &str, number_of_sites: u8, number_of_tags: u8) -> Report {
+        self.generate_ranked_report(
+            padron,
+            &Ranking::chatty(number_of_sites),
+            &Ranking::chatty(number_of_tags),
+        )
+    }
 
+    /// Generates the report with the site collection information, ranking the chatty sites
+    /// and tags with the provided rankings.
+    ///
+    /// # Arguments
+    ///
+    /// * `padron` - The padron number.
+    /// * `chatty_sites` - The ranking of the chatty sites included in the totals.
+    /// * `chatty_tags` - The ranking of the chatty tags included for each site and in the totals.
+    ///
+    /// # Returns
+    ///
+    /// The report with the site collection information, without diagnostics nor related tags.
+    pub fn generate_ranked_report(
+        &self,
+        padron: &str,
+        chatty_sites: &Ranking,
+        chatty_tags: &Ranking,
+    ) -> Report {
         let tags = self.get_all_tags();
-
-        data["tags"] = tags.generate_json();
-        data["totals"] = self.generate_totals_json(tags);
-        data
+        Report {
+            diagnostics: None,
+            leaderboards: BTreeMap::new(),
+            padron: padron.to_string(),
+            related_tags: None,
+            sites: self.generate_sites_report(chatty_tags),
+            tags: tags.generate_report(),
+            totals: self.generate_totals_report(&tags, chatty_sites, chatty_tags),
+        }
     }
 }
 
@@ -303,3 +924,219 @@ impl Default for SitesCollection {
         Self::new()
     }
 }
+
+/// Builder of SitesCollection instances.
+///
+/// # Attributes
+///
+/// * `num_threads` - The number of threads of the collection's pool. If it is None, the collection has no pool.
+/// * `strict` - Whether the loading should fail on the first invalid line or file.
+/// * `strategy` - How the lines of each file are distributed among the threads.
+/// * `parser` - The parser of the questions of the lines.
+/// * `grouping` - The rule that assigns the files to their sites.
+/// * `duplicates` - What is done with the sites stored in more than one file.
+#[derive(Debug, Default)]
+pub struct SitesCollectionBuilder {
+    num_threads: Option<u8>,
+    strict: bool,
+    strategy: ReadStrategy,
+    parser: QuestionParser,
+    grouping: SiteGrouping,
+    duplicates: DuplicateSites,
+}
+
+impl SitesCollectionBuilder {
+    /// Creates a new builder with the default configuration.
+    ///
+    /// # Returns
+    ///
+    /// A new SitesCollectionBuilder instance.
+    pub fn new() -> Self {
+        SitesCollectionBuilder::default()
+    }
+
+    /// Sets the amount of threads of the collection's own thread pool.
+    /// If the amount is 0, rayon decides the amount of threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_threads` - The number of threads to be used by the collection.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the amount of threads set.
+    pub fn num_threads(mut self, num_threads: u8) -> Self {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// Sets whether the loading of the sites should fail on the first invalid line or file.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether the loading should fail on the first problem.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the strict mode set.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets how the lines of each file are distributed among the threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy used to read the files.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the read strategy set.
+    pub fn read_strategy(mut self, strategy: ReadStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the rule that assigns the files to the sites they belong to.
+    ///
+    /// # Arguments
+    ///
+    /// * `grouping` - The rule that assigns the files to their sites.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the grouping rule set.
+    pub fn site_grouping(mut self, grouping: SiteGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Sets what is done with the sites stored in more than one file.
+    ///
+    /// # Arguments
+    ///
+    /// * `duplicates` - Whether the sites are merged or the loading fails.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the policy for duplicate sites set.
+    pub fn duplicate_sites(mut self, duplicates: DuplicateSites) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Sets the shape of the JSON questions of the lines of the files.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the questions.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the input schema set.
+    pub fn input_schema(mut self, schema: InputSchema) -> Self {
+        self.parser.set_schema(schema);
+        self
+    }
+
+    /// Sets the field held by each of the texts of the questions, such as the title or the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout of the texts of the questions.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the text layout set.
+    pub fn text_layout(mut self, layout: TextLayout) -> Self {
+        self.parser.set_layout(layout);
+        self
+    }
+
+    /// Sets the tokenizer that counts the words of the texts of the questions.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokenizer` - The tokenizer.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the tokenizer set.
+    pub fn tokenizer(mut self, tokenizer: Box<dyn Tokenizer>) -> Self {
+        self.parser.set_tokenizer(tokenizer);
+        self
+    }
+
+    /// Sets the filter the questions must pass to be aggregated.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter of the questions, or None to aggregate every question.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the filter set.
+    pub fn question_filter(mut self, filter: Option<QuestionFilter>) -> Self {
+        self.parser.set_filter(filter);
+        self
+    }
+
+    /// Sets whether the sites count the questions in which each pair of tags appeared, which is
+    /// needed by the related tags report.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_pairs` - Whether the pairs of tags are counted.
+    ///
+    /// # Returns
+    ///
+    /// The builder with the counting of the pairs of tags set.
+    pub fn tag_pairs(mut self, tag_pairs: bool) -> Self {
+        self.parser.set_tag_pairs(tag_pairs);
+        self
+    }
+
+    /// Builds the collection. If there was an error while building the threadpool, it returns an error.
+    ///
+    /// # Returns
+    ///
+    /// A result with the new SitesCollection instance or a ParsingError.
+    pub fn build(self) -> Result<SitesCollection, ParsingError> {
+        let pool = match self.num_threads {
+            Some(num_threads) => Some(build_thread_pool(num_threads)?),
+            None => None,
+        };
+        Ok(SitesCollection {
+            sites: None,
+            strict: self.strict,
+            strategy: self.strategy,
+            parser: self.parser,
+            grouping: self.grouping,
+            duplicates: self.duplicates,
+            pool,
+        })
+    }
+}
+
+/// Builds a thread pool with the provided amount of threads.
+/// If there was an error while building the threadpool, it returns an error.
+///
+/// # Arguments
+///
+/// * `num_threads` - The number of threads of the pool.
+///
+/// # Returns
+///
+/// A result with the thread pool or a ParsingError.
+fn build_thread_pool(num_threads: u8) -> Result<ThreadPool, ParsingError> {
+    match ThreadPoolBuilder::new()
+        .num_threads(num_threads as usize)
+        .build()
+    {
+        Ok(pool) => Ok(pool),
+        Err(error) => Err(ParsingError::ErrorWhileBuildingThreadpool(
+            error.to_string(),
+        )),
+    }
+}
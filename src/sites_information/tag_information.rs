@@ -1,10 +1,28 @@
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    question_information::FieldWords,
+    ranking::{words_questions_ratio, Rankable, RankingMetric},
+    report::TagReport,
+    word_distribution::WordDistribution,
+};
 
 /// Represents the information of a tag.
-#[derive(Debug, Clone, Copy)]
+///
+/// # Attributes
+///
+/// * `question_count` - The amount of questions with the tag.
+/// * `word_count` - The amount of words of the questions with the tag.
+/// * `co_occurrences` - The amount of times the tag appeared together with another tag.
+/// * `distribution` - The distribution of the amount of words of the questions with the tag.
+/// * `fields` - The amount of words in the titles and bodies of the questions with the tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagInformation {
-    pub question_count: u32,
-    pub word_count: u32,
+    pub question_count: u64,
+    pub word_count: u64,
+    pub co_occurrences: u64,
+    pub distribution: WordDistribution,
+    pub fields: FieldWords,
 }
 
 impl TagInformation {
@@ -17,52 +35,124 @@ impl TagInformation {
     /// # Returns
     ///
     /// A new tag information instance.
-    pub fn new(word_count: u32) -> Self {
+    pub fn new(word_count: u64) -> Self {
+        let mut distribution = WordDistribution::new();
+        distribution.add(word_count);
         TagInformation {
             question_count: 1,
             word_count,
+            co_occurrences: 0,
+            distribution,
+            fields: FieldWords::default(),
         }
     }
 
     /// Adds an appearance of the tag with the given word count.
+    /// The counters saturate instead of overflowing.
     ///
     /// # Arguments
     ///
     /// * `word_count` - The word count of the tag.
-    pub fn add_appearance(&mut self, word_count: u32) {
-        self.word_count += word_count;
-        self.question_count += 1;
+    pub fn add_appearance(&mut self, word_count: u64) {
+        self.word_count = self.word_count.saturating_add(word_count);
+        self.question_count = self.question_count.saturating_add(1);
+        self.distribution.add(word_count);
+    }
+
+    /// Adds the words of the title and the body of a question with the tag.
+    /// The counters saturate instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - The amount of words in the title and in the body of the question.
+    pub fn add_fields(&mut self, fields: FieldWords) {
+        self.fields.add(fields);
+    }
+
+    /// Adds the amount of other tags that appeared together with the tag in a question.
+    /// The counter saturates instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount of other tags of the question.
+    pub fn add_co_occurrences(&mut self, amount: u64) {
+        self.co_occurrences = self.co_occurrences.saturating_add(amount);
     }
 
     /// Merges the information of the tag with the information of another tag.
+    /// The counters saturate instead of overflowing.
     ///
     /// # Arguments
     ///
     /// * `other` - The other tag to merge with
-    pub fn merge(&mut self, other: Self) {
-        self.question_count += other.question_count;
-        self.word_count += other.word_count;
+    pub fn merge(&mut self, other: &Self) {
+        self.question_count = self.question_count.saturating_add(other.question_count);
+        self.word_count = self.word_count.saturating_add(other.word_count);
+        self.co_occurrences = self.co_occurrences.saturating_add(other.co_occurrences);
+        self.distribution.merge(&other.distribution);
+        self.fields.add(other.fields);
+    }
+
+    /// Calculates the ratio between the amount of words and the amount of questions of the tag.
+    ///
+    /// # Returns
+    ///
+    /// The ratio of words/questions, or None if the tag has no questions.
+    pub fn calculate_words_questions_ratio(&self) -> Option<f64> {
+        words_questions_ratio(self.word_count, self.question_count)
+    }
+
+    /// Generates the report of the tag.
+    ///
+    /// # Returns
+    ///
+    /// The report of the tag.
+    pub fn generate_report(&self) -> TagReport {
+        TagReport {
+            distribution: self.distribution.generate_report(),
+            fields: self.fields.generate_report(self.question_count),
+            questions: self.question_count,
+            words: self.word_count,
+        }
     }
+}
 
-    /// Returns the word count of the tag.
+impl Rankable for TagInformation {
+    /// Returns the amount of questions with the tag.
     ///
     /// # Returns
     ///
-    /// The word count of the tag.
-    pub fn calculate_words_questions_ratio(&self) -> f64 {
-        self.word_count as f64 / self.question_count as f64
+    /// The amount of questions.
+    fn question_count(&self) -> u64 {
+        self.question_count
     }
 
-    /// Generates a JSON with the tag information.
+    /// Returns the amount of words of the questions with the tag.
     ///
     /// # Returns
     ///
-    /// A JSON with the tag information.
-    pub fn generate_json(&self) -> Value {
-        let mut tag_data = Value::Object(serde_json::Map::new());
-        tag_data["questions"] = Value::from(self.question_count);
-        tag_data["words"] = Value::from(self.word_count);
-        tag_data
+    /// The amount of words.
+    fn word_count(&self) -> u64 {
+        self.word_count
+    }
+
+    /// Returns the value of the provided metric for the tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The metric to calculate.
+    ///
+    /// # Returns
+    ///
+    /// The value of the metric, or None if the tag has no value for it.
+    fn metric_value(&self, metric: RankingMetric) -> Option<f64> {
+        match metric {
+            RankingMetric::Questions => Some(self.question_count as f64),
+            RankingMetric::Words => Some(self.word_count as f64),
+            RankingMetric::Ratio => self.calculate_words_questions_ratio(),
+            RankingMetric::MedianWords => self.distribution.median(),
+            RankingMetric::CoOccurrences => Some(self.co_occurrences as f64),
+        }
     }
 }
 
@@ -88,8 +178,51 @@ mod tests {
     #[test]
     fn test_merge() {
         let mut tag_info = TagInformation::new(10);
-        tag_info.merge(TagInformation::new(5));
+        tag_info.merge(&TagInformation::new(5));
         assert_eq!(tag_info.question_count, 2);
         assert_eq!(tag_info.word_count, 15);
     }
+
+    #[test]
+    fn median_words_and_co_occurrences_are_ranked() {
+        let mut tag_info = TagInformation::new(10);
+        tag_info.add_appearance(30);
+        tag_info.add_appearance(20);
+        tag_info.add_co_occurrences(2);
+        tag_info.merge(&TagInformation::new(40));
+        assert_eq!(
+            tag_info.metric_value(RankingMetric::MedianWords),
+            Some(20.0)
+        );
+        assert_eq!(
+            tag_info.metric_value(RankingMetric::CoOccurrences),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn counters_go_beyond_u32() {
+        let mut tag_info = TagInformation::new(u32::MAX as u64);
+        tag_info.add_appearance(u32::MAX as u64);
+        tag_info.merge(&TagInformation {
+            question_count: u32::MAX as u64,
+            word_count: 2,
+            ..TagInformation::new(0)
+        });
+        assert_eq!(tag_info.question_count, u32::MAX as u64 + 2);
+        assert_eq!(tag_info.word_count, 2 * u32::MAX as u64 + 2);
+    }
+
+    #[test]
+    fn counters_saturate() {
+        let mut tag_info = TagInformation::new(u64::MAX);
+        tag_info.add_appearance(1);
+        tag_info.merge(&TagInformation {
+            question_count: u64::MAX,
+            word_count: 1,
+            ..TagInformation::new(0)
+        });
+        assert_eq!(tag_info.question_count, u64::MAX);
+        assert_eq!(tag_info.word_count, u64::MAX);
+    }
 }
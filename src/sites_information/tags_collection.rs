@@ -1,11 +1,16 @@
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
 
-use super::tag_information::TagInformation;
+use super::{
+    question_information::{unique_tags, FieldWords},
+    ranking::Ranking,
+    report::TagReport,
+    tag_information::TagInformation,
+};
 
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
 
 /// Represents a collection of tags with their information.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagsCollection {
     tags: HashMap<String, TagInformation>,
 }
@@ -32,7 +37,7 @@ impl TagsCollection {
         for (tag, other_info) in other.tags {
             match self.tags.get_mut(&tag) {
                 Some(tag_info) => {
-                    tag_info.merge(other_info);
+                    tag_info.merge(&other_info);
                 }
                 None => {
                     self.tags.insert(tag, other_info);
@@ -51,73 +56,95 @@ impl TagsCollection {
         for (tag, other_info) in &other.tags {
             match self.tags.get_mut(tag) {
                 Some(tag_info) => {
-                    tag_info.merge(*other_info);
+                    tag_info.merge(other_info);
                 }
                 None => {
-                    self.tags.insert(tag.clone(), *other_info);
+                    self.tags.insert(tag.clone(), other_info.clone());
                 }
             }
         }
     }
 
-    /// Adds tags to the collection with the given word count.
+    /// Adds the tags of a question to the collection with the given word count.
+    /// Each tag is counted as appearing together with the other tags of the question, and a repeated
+    /// tag is only counted once.
     ///
     /// # Arguments
     ///
     /// * `tags` - The tags to add.
     /// * `words` - The word count of the tags.
-    pub fn add_tags(&mut self, tags: Vec<String>, words: u32) {
+    /// * `fields` - The word count of the title and the body of the question.
+    pub fn add_tags(&mut self, tags: Vec<String>, words: u64, fields: FieldWords) {
+        let tags = unique_tags(tags);
+        let others = (tags.len() as u64).saturating_sub(1);
         for tag in tags {
-            match self.tags.get_mut(&tag) {
-                Some(tag_info) => {
+            let tag_info = match self.tags.entry(tag) {
+                Entry::Occupied(entry) => {
+                    let tag_info = entry.into_mut();
                     tag_info.add_appearance(words);
+                    tag_info
                 }
-                None => {
-                    self.tags.insert(tag, TagInformation::new(words));
-                }
-            }
+                Entry::Vacant(entry) => entry.insert(TagInformation::new(words)),
+            };
+            tag_info.add_co_occurrences(others);
+            tag_info.add_fields(fields);
         }
     }
 
-    /// Generates a JSON with the tags collection information.
+    /// Generates the report of each tag of the collection.
     ///
     /// # Returns
     ///
-    /// A JSON with the tags collection information.
-    pub fn generate_json(&self) -> Value {
-        let mut tags_data = Value::Object(serde_json::Map::new());
-        for (tag, tag_information) in &self.tags {
-            tags_data[tag] = tag_information.generate_json();
-        }
-        tags_data
+    /// The report of each tag, by name.
+    pub fn generate_report(&self) -> BTreeMap<String, TagReport> {
+        self.tags
+            .iter()
+            .map(|(tag, tag_information)| (tag.clone(), tag_information.generate_report()))
+            .collect()
+    }
+
+    /// Returns the tags with the highest ratio of words/questions.
+    ///
+    /// # Arguments
+    ///
+    /// * `number_of_tags` - The number of tags to return.
+    ///
+    /// # Returns
+    ///
+    /// The names of the most chatty tags.
+    pub fn get_chatty_tags(&self, number_of_tags: u8) -> Vec<String> {
+        self.rank_tags(&Ranking::chatty(number_of_tags))
     }
 
-    /// Generates a JSON with the most chatty tags.
+    /// Returns the tags ranked by the provided ranking.
     ///
     /// # Arguments
     ///
-    /// * `number_of_tags` - The number of tags to include in the JSON.
+    /// * `ranking` - The metric, order, threshold and limit of the ranking.
     ///
     /// # Returns
     ///
-    /// A JSON with the most chatty tags.
-    pub fn generate_chatty_tags_json(&self, number_of_tags: u8) -> Value {
-        let mut tags: Vec<(&String, &TagInformation)> = self.tags.iter().collect();
-        tags.sort_by(|a, b| {
-            let ratio_a = a.1.calculate_words_questions_ratio();
-            let ratio_b = b.1.calculate_words_questions_ratio();
-            ratio_b
-                .partial_cmp(&ratio_a)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        let top_tags = tags
-            .into_iter()
-            .take(number_of_tags as usize)
-            .map(|(tag, _)| Value::String(tag.clone()))
-            .collect();
-
-        Value::Array(top_tags)
+    /// The names of the ranked tags.
+    pub fn rank_tags(&self, ranking: &Ranking) -> Vec<String> {
+        ranking.rank(self.tags.iter().map(|(tag, info)| (tag.as_str(), info)))
+    }
+
+    /// Returns the tags ranked by the provided ranking, pulling the ratio of the tags with few
+    /// questions toward the provided mean if the ranking smooths the ratio.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranking` - The metric, order, threshold and limit of the ranking.
+    /// * `mean` - The ratio of words/questions used as the prior of the Bayesian average.
+    ///
+    /// # Returns
+    ///
+    /// The names of the ranked tags.
+    pub fn rank_tags_with_mean(&self, ranking: &Ranking, mean: f64) -> Vec<String> {
+        ranking.rank_with_mean(
+            self.tags.iter().map(|(tag, info)| (tag.as_str(), info)),
+            mean,
+        )
     }
 }
 
@@ -135,6 +162,7 @@ impl Default for TagsCollection {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sites_information::ranking::RankingMetric;
 
     #[test]
     fn new_tag_collection_is_empty() {
@@ -145,19 +173,63 @@ mod tests {
     #[test]
     fn add_tags_to_collection() {
         let mut collection = TagsCollection::new();
-        collection.add_tags(vec!["tag1".to_string(), "tag2".to_string()], 10);
+        collection.add_tags(
+            vec!["tag1".to_string(), "tag2".to_string()],
+            10,
+            FieldWords::default(),
+        );
         assert_eq!(collection.tags.len(), 2);
         assert_eq!(collection.tags.get("tag1").unwrap().word_count, 10);
         assert_eq!(collection.tags.get("tag2").unwrap().word_count, 10);
     }
 
+    #[test]
+    fn chatty_tags_ties_are_broken_by_name() {
+        let mut collection = TagsCollection::new();
+        collection.add_tags(
+            vec!["b".to_string(), "c".to_string()],
+            10,
+            FieldWords::default(),
+        );
+        collection.add_tags(vec!["a".to_string()], 10, FieldWords::default());
+        collection.add_tags(vec!["d".to_string()], 20, FieldWords::default());
+        assert_eq!(collection.get_chatty_tags(3), vec!["d", "a", "b"]);
+    }
+
+    #[test]
+    fn tags_are_ranked_by_co_occurrences() {
+        let mut collection = TagsCollection::new();
+        collection.add_tags(
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            10,
+            FieldWords::default(),
+        );
+        collection.add_tags(
+            vec!["b".to_string(), "c".to_string()],
+            10,
+            FieldWords::default(),
+        );
+        collection.add_tags(vec!["c".to_string()], 10, FieldWords::default());
+        let ranking = Ranking::new(RankingMetric::CoOccurrences).limit(2);
+        assert_eq!(collection.rank_tags(&ranking), vec!["b", "c"]);
+        assert_eq!(collection.tags["c"].co_occurrences, 3);
+    }
+
     #[test]
     fn merge_collections() {
         let mut collection1 = TagsCollection::new();
-        collection1.add_tags(vec!["tag1".to_string(), "tag2".to_string()], 10);
+        collection1.add_tags(
+            vec!["tag1".to_string(), "tag2".to_string()],
+            10,
+            FieldWords::default(),
+        );
 
         let mut collection2 = TagsCollection::new();
-        collection2.add_tags(vec!["tag1".to_string(), "tag3".to_string()], 20);
+        collection2.add_tags(
+            vec!["tag1".to_string(), "tag3".to_string()],
+            20,
+            FieldWords::default(),
+        );
 
         collection1.merge(collection2);
 
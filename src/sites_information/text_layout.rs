@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/// Enum that represents what an entry of the texts of a question holds.
+///
+/// # Variants
+///
+/// * `Title` - The title of the question.
+/// * `Body` - The body of the question.
+/// * `Other` - Any other text, such as answers or comments. It only counts in the total of words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextField {
+    Title,
+    Body,
+    Other,
+}
+
+impl TextField {
+    /// Returns the field with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the field: title, body or other.
+    ///
+    /// # Returns
+    ///
+    /// The field, or None if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "title" => Some(TextField::Title),
+            "body" => Some(TextField::Body),
+            "other" => Some(TextField::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the layout of the texts of the questions of a dataset: the field held by each
+/// position of the texts. The texts beyond the layout hold the same field as its last position.
+///
+/// # Attributes
+///
+/// * `fields` - The field held by each position of the texts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextLayout {
+    fields: Vec<TextField>,
+}
+
+impl TextLayout {
+    /// Creates a layout with the provided fields. An empty layout counts every text as other.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - The field held by each position of the texts.
+    ///
+    /// # Returns
+    ///
+    /// A new TextLayout instance.
+    pub fn new(fields: Vec<TextField>) -> Self {
+        TextLayout { fields }
+    }
+
+    /// Parses a layout from the comma separated names of its fields, such as `title,body,other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The names of the fields.
+    ///
+    /// # Returns
+    ///
+    /// The layout, or None if a name is unknown.
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        let fields = spec
+            .split(',')
+            .map(|name| TextField::from_name(name.trim()))
+            .collect::<Option<Vec<TextField>>>()?;
+        Some(TextLayout::new(fields))
+    }
+
+    /// Returns the field held by the text at the provided position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the text, starting at zero.
+    ///
+    /// # Returns
+    ///
+    /// The field of the text.
+    pub fn field_at(&self, position: usize) -> TextField {
+        match self.fields.get(position).or(self.fields.last()) {
+            Some(field) => *field,
+            None => TextField::Other,
+        }
+    }
+}
+
+impl Default for TextLayout {
+    /// Returns the layout of the StackExchange dumps: the title followed by the body.
+    ///
+    /// # Returns
+    ///
+    /// The default text layout.
+    fn default() -> Self {
+        TextLayout::new(vec![TextField::Title, TextField::Body])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texts_beyond_the_layout_repeat_its_last_field() {
+        let layout = TextLayout::default();
+        assert_eq!(layout.field_at(0), TextField::Title);
+        assert_eq!(layout.field_at(1), TextField::Body);
+        assert_eq!(layout.field_at(5), TextField::Body);
+        assert_eq!(TextLayout::new(Vec::new()).field_at(0), TextField::Other);
+    }
+
+    #[test]
+    fn layouts_are_parsed_from_the_names_of_the_fields() {
+        let layout = TextLayout::from_spec("body, title,other").unwrap();
+        assert_eq!(layout.field_at(0), TextField::Body);
+        assert_eq!(layout.field_at(1), TextField::Title);
+        assert_eq!(layout.field_at(3), TextField::Other);
+        assert_eq!(TextLayout::from_spec("title,answers"), None);
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt::Debug;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Counts the words of the texts of the questions.
+/// Tokenizers are shared by the threads that parse the lines, so they must be Send and Sync.
+pub trait Tokenizer: Debug + Send + Sync {
+    /// Returns the amount of words of a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text whose words are counted.
+    ///
+    /// # Returns
+    ///
+    /// The amount of words.
+    fn count_words(&self, text: &str) -> u64;
+
+    /// Returns the name of the tokenizer, used to detect when the words were counted differently.
+    ///
+    /// # Returns
+    ///
+    /// The name of the tokenizer.
+    fn name(&self) -> &'static str;
+}
+
+/// Counts as a word every run of characters between whitespace, including markup,
+/// code and punctuation. It is the fastest tokenizer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    /// Returns the amount of runs of characters between whitespace.
+    fn count_words(&self, text: &str) -> u64 {
+        text.split_whitespace().count() as u64
+    }
+
+    /// Returns the name of the tokenizer.
+    fn name(&self) -> &'static str {
+        "whitespace"
+    }
+}
+
+/// Counts the words found by the Unicode word boundaries (UAX #29), ignoring punctuation
+/// and symbols, so `don't` is one word and `foo-bar` is two.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnicodeTokenizer;
+
+impl Tokenizer for UnicodeTokenizer {
+    /// Returns the amount of Unicode words.
+    fn count_words(&self, text: &str) -> u64 {
+        text.unicode_words().count() as u64
+    }
+
+    /// Returns the name of the tokenizer.
+    fn name(&self) -> &'static str {
+        "unicode"
+    }
+}
+
+/// Counts the Unicode words of the prose of a text, without its code and markup:
+/// fenced and inline Markdown code, `<pre>` and `<code>` blocks and the HTML tags are dropped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProseTokenizer;
+
+impl Tokenizer for ProseTokenizer {
+    /// Returns the amount of Unicode words of the prose.
+    fn count_words(&self, text: &str) -> u64 {
+        let text = remove_between(text, "```", "```");
+        let text = remove_between(&text, "<pre", "</pre>");
+        let text = remove_between(&text, "<code", "</code>");
+        let text = remove_between(&text, "`", "`");
+        let text = remove_tags(&text);
+        UnicodeTokenizer.count_words(&text)
+    }
+
+    /// Returns the name of the tokenizer.
+    fn name(&self) -> &'static str {
+        "prose"
+    }
+}
+
+/// Replaces with a space every part of the text that starts with the opening marker and ends
+/// with the closing marker, markers included. An unclosed opening marker is kept as text.
+/// The markers are compared ignoring the ASCII case.
+fn remove_between(text: &str, open: &str, close: &str) -> String {
+    let lowercase = text.to_ascii_lowercase();
+    let mut kept = String::with_capacity(text.len());
+    let mut position = 0;
+    while let Some(start) = lowercase[position..]
+        .find(open)
+        .map(|start| start + position)
+    {
+        let after_open = start + open.len();
+        kept.push_str(&text[position..start]);
+        position = match lowercase[after_open..].find(close) {
+            Some(end) => {
+                kept.push(' ');
+                after_open + end + close.len()
+            }
+            None => {
+                kept.push_str(&text[start..after_open]);
+                after_open
+            }
+        };
+    }
+    kept.push_str(&text[position..]);
+    kept
+}
+
+/// Replaces with a space every HTML tag of the text. A `<` only starts a tag when it is followed
+/// by a letter, `/` or `!` and closed by a `>` before the next `<` or line break, so comparisons
+/// such as `x < 5` are kept as text.
+fn remove_tags(text: &str) -> String {
+    let mut kept = String::with_capacity(text.len());
+    let mut position = 0;
+    while let Some(start) = text[position..].find('<').map(|start| start + position) {
+        let after_open = start + 1;
+        let starts_tag = text[after_open..]
+            .chars()
+            .next()
+            .is_some_and(|next| next.is_ascii_alphabetic() || next == '/' || next == '!');
+        let end = text[after_open..]
+            .find(['<', '>', '\n'])
+            .map(|end| after_open + end)
+            .filter(|end| text[*end..].starts_with('>'));
+        kept.push_str(&text[position..start]);
+        position = match end {
+            Some(end) if starts_tag => {
+                kept.push(' ');
+                end + 1
+            }
+            _ => {
+                kept.push('<');
+                after_open
+            }
+        };
+    }
+    kept.push_str(&text[position..]);
+    kept
+}
+
+/// Enum that represents the tokenizers that can be selected by name.
+///
+/// # Variants
+///
+/// * `Whitespace` - Counts the runs of characters between whitespace.
+/// * `Unicode` - Counts the words found by the Unicode word boundaries.
+/// * `Prose` - Counts the Unicode words without code and markup.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TokenizerKind {
+    #[default]
+    Whitespace,
+    Unicode,
+    Prose,
+}
+
+impl TokenizerKind {
+    /// Returns the tokenizer with the provided name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tokenizer: whitespace, unicode or prose.
+    ///
+    /// # Returns
+    ///
+    /// The kind of tokenizer, or None if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "whitespace" => Some(TokenizerKind::Whitespace),
+            "unicode" => Some(TokenizerKind::Unicode),
+            "prose" => Some(TokenizerKind::Prose),
+            _ => None,
+        }
+    }
+
+    /// Creates the tokenizer of this kind.
+    ///
+    /// # Returns
+    ///
+    /// The tokenizer.
+    pub fn tokenizer(&self) -> Box<dyn Tokenizer> {
+        match self {
+            TokenizerKind::Whitespace => Box::new(WhitespaceTokenizer),
+            TokenizerKind::Unicode => Box::new(UnicodeTokenizer),
+            TokenizerKind::Prose => Box::new(ProseTokenizer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str =
+        "Why doesn't <b>this</b> compile? <pre><code>let x = 1;</code></pre> Use `x + 1` instead.";
+
+    #[test]
+    fn whitespace_counts_markup_and_code() {
+        assert_eq!(WhitespaceTokenizer.count_words(TEXT), 13);
+    }
+
+    #[test]
+    fn unicode_skips_punctuation() {
+        assert_eq!(UnicodeTokenizer.count_words("don't -- foo-bar ..."), 3);
+        assert_eq!(UnicodeTokenizer.count_words(TEXT), 17);
+    }
+
+    #[test]
+    fn prose_drops_code_and_markup() {
+        assert_eq!(ProseTokenizer.count_words(TEXT), 6);
+        assert_eq!(
+            ProseTokenizer.count_words("Before\n```\nfn main() {}\n```\nafter <PRE>code"),
+            3
+        );
+    }
+
+    #[test]
+    fn prose_keeps_comparisons() {
+        let text = "Why is x < 5 true when y is larger than ten";
+        assert_eq!(ProseTokenizer.count_words(text), 11);
+        assert_eq!(ProseTokenizer.count_words("if a < b and b > c"), 6);
+        assert_eq!(
+            ProseTokenizer.count_words("a <b>bold</b> <!-- note --> c"),
+            3
+        );
+    }
+
+    #[test]
+    fn prose_keeps_the_text_after_a_single_backtick() {
+        assert_eq!(
+            ProseTokenizer.count_words("Press the ` key to open the console"),
+            7
+        );
+        assert_eq!(ProseTokenizer.count_words("Use `x` not ` here"), 3);
+    }
+
+    #[test]
+    fn tokenizers_are_selected_by_name() {
+        assert_eq!(
+            TokenizerKind::from_name("prose"),
+            Some(TokenizerKind::Prose)
+        );
+        assert_eq!(TokenizerKind::from_name("words"), None);
+        let tokenizer = TokenizerKind::Unicode.tokenizer();
+        assert_eq!(tokenizer.count_words("a, b"), 2);
+        for kind in [
+            TokenizerKind::Whitespace,
+            TokenizerKind::Unicode,
+            TokenizerKind::Prose,
+        ] {
+            assert_eq!(
+                TokenizerKind::from_name(kind.tokenizer().name()),
+                Some(kind)
+            );
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::report::DistributionReport;
+
+/// Values below this limit are counted exactly, each one in its own bucket.
+const EXACT_LIMIT: u64 = 64;
+/// Amount of buckets in which each power of two above the exact limit is split.
+/// The relative error of a value read from a bucket is below 1 / (2 * SUB_BUCKETS).
+const SUB_BUCKETS: u64 = 16;
+/// Amount of bits needed to index the sub buckets of a power of two.
+const SUB_BUCKET_BITS: u32 = SUB_BUCKETS.trailing_zeros();
+/// Exponent of the first power of two that is not counted exactly.
+const FIRST_EXPONENT: u32 = EXACT_LIMIT.trailing_zeros();
+
+/// Represents the distribution of the amount of words of a set of questions.
+/// It is a log-linear histogram: small amounts are counted exactly and larger ones in buckets
+/// whose width grows with the value, so it takes little memory and can be merged.
+///
+/// # Attributes
+///
+/// * `buckets` - The amount of questions in each non-empty bucket, by bucket index.
+/// * `count` - The amount of questions in the distribution.
+/// * `min` - The lowest amount of words, exact. Meaningless if the distribution is empty.
+/// * `max` - The highest amount of words, exact. Meaningless if the distribution is empty.
+/// * `sum` - The sum of the amounts of words.
+/// * `sum_of_squares` - The sum of the squares of the amounts of words.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WordDistribution {
+    buckets: BTreeMap<u16, u64>,
+    count: u64,
+    min: u64,
+    max: u64,
+    sum: u128,
+    sum_of_squares: u128,
+}
+
+impl WordDistribution {
+    /// Creates an empty distribution.
+    ///
+    /// # Returns
+    ///
+    /// A new WordDistribution instance.
+    pub fn new() -> Self {
+        WordDistribution::default()
+    }
+
+    /// Adds the amount of words of a question to the distribution.
+    /// The counters saturate instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The amount of words of the question.
+    pub fn add(&mut self, words: u64) {
+        let bucket = self.buckets.entry(bucket_of(words)).or_insert(0);
+        *bucket = bucket.saturating_add(1);
+        if self.count == 0 {
+            self.min = words;
+            self.max = words;
+        } else {
+            self.min = self.min.min(words);
+            self.max = self.max.max(words);
+        }
+        self.count = self.count.saturating_add(1);
+        self.sum = self.sum.saturating_add(words as u128);
+        self.sum_of_squares = self
+            .sum_of_squares
+            .saturating_add((words as u128).saturating_mul(words as u128));
+    }
+
+    /// Merges the distribution with another distribution.
+    /// The counters saturate instead of overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other distribution to merge with
+    pub fn merge(&mut self, other: &Self) {
+        for (bucket, count) in &other.buckets {
+            let own = self.buckets.entry(*bucket).or_insert(0);
+            *own = own.saturating_add(*count);
+        }
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            self.min = other.min;
+            self.max = other.max;
+        } else {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+        self.count = self.count.saturating_add(other.count);
+        self.sum = self.sum.saturating_add(other.sum);
+        self.sum_of_squares = self.sum_of_squares.saturating_add(other.sum_of_squares);
+    }
+
+    /// Returns the amount of questions in the distribution.
+    ///
+    /// # Returns
+    ///
+    /// The amount of questions.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns an approximation of the provided quantile of the amount of words.
+    /// Amounts below 64 are exact and larger ones have a relative error below 4%.
+    ///
+    /// # Arguments
+    ///
+    /// * `quantile` - The quantile, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// The amount of words at the quantile, or None if the distribution is empty.
+    pub fn quantile(&self, quantile: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0u64;
+        for (bucket, count) in &self.buckets {
+            seen = seen.saturating_add(*count);
+            if seen >= rank {
+                return Some(representative_of(*bucket));
+            }
+        }
+        self.buckets
+            .keys()
+            .next_back()
+            .map(|bucket| representative_of(*bucket))
+    }
+
+    /// Returns an approximation of the median amount of words.
+    ///
+    /// # Returns
+    ///
+    /// The median amount of words, or None if the distribution is empty.
+    pub fn median(&self) -> Option<f64> {
+        self.quantile(0.5)
+    }
+
+    /// Returns the lowest amount of words, which is exact.
+    ///
+    /// # Returns
+    ///
+    /// The lowest amount of words, or None if the distribution is empty.
+    pub fn min(&self) -> Option<u64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Returns the highest amount of words, which is exact.
+    ///
+    /// # Returns
+    ///
+    /// The highest amount of words, or None if the distribution is empty.
+    pub fn max(&self) -> Option<u64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Returns the population standard deviation of the amount of words.
+    /// It is calculated from exact sums, so it does not depend on the order of the merges.
+    ///
+    /// # Returns
+    ///
+    /// The standard deviation, or None if the distribution is empty.
+    pub fn standard_deviation(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let count = self.count as f64;
+        let mean = self.sum as f64 / count;
+        let variance = self.sum_of_squares as f64 / count - mean * mean;
+        Some(variance.max(0.0).sqrt())
+    }
+
+    /// Generates the summary of the distribution.
+    ///
+    /// # Returns
+    ///
+    /// The summary of the distribution, or None if the distribution is empty.
+    pub fn generate_report(&self) -> Option<DistributionReport> {
+        Some(DistributionReport {
+            max: self.max()?,
+            median: self.median()?,
+            min: self.min()?,
+            p90: self.quantile(0.9)?,
+            p99: self.quantile(0.99)?,
+            std_dev: self.standard_deviation()?,
+        })
+    }
+}
+
+/// Returns the index of the bucket where the provided amount of words is counted.
+fn bucket_of(words: u64) -> u16 {
+    if words < EXACT_LIMIT {
+        return words as u16;
+    }
+    let exponent = 63 - words.leading_zeros();
+    let sub_bucket = (words >> (exponent - SUB_BUCKET_BITS)) & (SUB_BUCKETS - 1);
+    (EXACT_LIMIT + (exponent - FIRST_EXPONENT) as u64 * SUB_BUCKETS + sub_bucket) as u16
+}
+
+/// Returns the value that represents the amounts of words counted in the provided bucket:
+/// the amount itself for exact buckets, or the middle of the range of the bucket otherwise.
+fn representative_of(bucket: u16) -> f64 {
+    let bucket = bucket as u64;
+    if bucket < EXACT_LIMIT {
+        return bucket as f64;
+    }
+    let exponent = (bucket - EXACT_LIMIT) / SUB_BUCKETS + FIRST_EXPONENT as u64;
+    let sub_bucket = (bucket - EXACT_LIMIT) % SUB_BUCKETS;
+    let width = 1u64 << (exponent - SUB_BUCKET_BITS as u64);
+    let lower = (SUB_BUCKETS + sub_bucket) * width;
+    lower as f64 + (width as f64 - 1.0) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_amounts_are_exact() {
+        let mut distribution = WordDistribution::new();
+        for words in [1, 2, 3, 10, 50] {
+            distribution.add(words);
+        }
+        assert_eq!(distribution.count(), 5);
+        assert_eq!(distribution.median(), Some(3.0));
+        assert_eq!(distribution.quantile(0.0), Some(1.0));
+        assert_eq!(distribution.quantile(1.0), Some(50.0));
+    }
+
+    #[test]
+    fn large_amounts_have_a_bounded_error() {
+        for words in [64, 100, 1_000, 123_456, u32::MAX as u64, u64::MAX] {
+            let mut distribution = WordDistribution::new();
+            distribution.add(words);
+            let median = distribution.median().unwrap();
+            let error = (median - words as f64).abs() / words as f64;
+            assert!(error < 0.04, "{} was read as {}", words, median);
+        }
+    }
+
+    #[test]
+    fn merged_distributions_count_every_question() {
+        let mut first = WordDistribution::new();
+        let mut second = WordDistribution::new();
+        for words in 0..10 {
+            first.add(words);
+            second.add(words + 10);
+        }
+        first.merge(&second);
+        assert_eq!(first.count(), 20);
+        assert_eq!(first.median(), Some(9.0));
+    }
+
+    #[test]
+    fn summaries_are_exact_where_possible() {
+        let mut first = WordDistribution::new();
+        let mut second = WordDistribution::new();
+        for words in [2, 4, 4, 4] {
+            first.add(words);
+        }
+        for words in [5, 5, 7, 9] {
+            second.add(words);
+        }
+        first.merge(&second);
+        first.merge(&WordDistribution::new());
+
+        let report = first.generate_report().unwrap();
+        assert_eq!(report.min, 2);
+        assert_eq!(report.max, 9);
+        assert_eq!(report.std_dev, 2.0);
+        assert_eq!(report.median, 4.0);
+        assert_eq!(report.p90, 9.0);
+        assert_eq!(report.p99, 9.0);
+    }
+
+    #[test]
+    fn empty_distributions_have_no_median() {
+        let distribution = WordDistribution::new();
+        assert_eq!(distribution.median(), None);
+        assert_eq!(distribution.min(), None);
+        assert_eq!(distribution.standard_deviation(), None);
+        assert_eq!(distribution.generate_report(), None);
+    }
+}
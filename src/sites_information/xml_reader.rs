@@ -0,0 +1,270 @@
+use std::{io::BufRead, path::Path};
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use rayon::prelude::*;
+
+use super::{
+    compression::open_file,
+    diagnostics::{DiagnosticKind, FileDiagnostics, LineDiagnostic},
+    input_schema::Post,
+    line_reader::StrictStop,
+    question_parser::QuestionParser,
+    site::Site,
+};
+
+/// Name of the elements of `Posts.xml` that hold a post each.
+const ROW_ELEMENT: &[u8] = b"row";
+/// Name of the attribute with the type of the post.
+const POST_TYPE_ATTRIBUTE: &[u8] = b"PostTypeId";
+/// Name of the attribute with the title of the post.
+const TITLE_ATTRIBUTE: &[u8] = b"Title";
+/// Name of the attribute with the body of the post.
+const BODY_ATTRIBUTE: &[u8] = b"Body";
+/// Name of the attribute with the tags of the post.
+const TAGS_ATTRIBUTE: &[u8] = b"Tags";
+
+/// Streams the `row` elements of a `Posts.xml` file, one post at a time, keeping track of the line
+/// where each of them starts.
+///
+/// # Attributes
+///
+/// * `reader` - The XML reader of the decompressed data of the file.
+/// * `buffer` - The buffer reused for the events of the reader.
+/// * `newlines` - The amount of line breaks read so far.
+/// * `partial_line` - Whether data was read after the last line break.
+/// * `finished` - Whether the end of the file, or an error that prevents reading it, was reached.
+struct PostRows<R: BufRead> {
+    reader: Reader<R>,
+    buffer: Vec<u8>,
+    newlines: u64,
+    partial_line: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> PostRows<R> {
+    /// Creates a stream of the rows of the provided reader.
+    fn new(reader: R) -> Self {
+        PostRows {
+            reader: Reader::from_reader(reader),
+            buffer: Vec::new(),
+            newlines: 0,
+            partial_line: false,
+            finished: false,
+        }
+    }
+
+    /// Returns the amount of lines read so far.
+    fn lines(&self) -> u64 {
+        self.newlines + u64::from(self.partial_line)
+    }
+
+    /// Counts the line breaks of the last event read.
+    fn count_lines(&mut self) {
+        for byte in &self.buffer {
+            self.partial_line = *byte != b'\n';
+            if !self.partial_line {
+                self.newlines += 1;
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PostRows<R> {
+    type Item = (u64, Result<Post, LineDiagnostic>);
+
+    /// Returns the next row with the number of the line where it starts, skipping the other
+    /// elements of the file. An XML error ends the stream, since the rest of the file can not be read.
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            self.buffer.clear();
+            let line_number = self.newlines + 1;
+            let row = match self.reader.read_event_into(&mut self.buffer) {
+                Ok(Event::Empty(element)) | Ok(Event::Start(element))
+                    if element.name().as_ref() == ROW_ELEMENT =>
+                {
+                    Some(read_post(line_number, &element))
+                }
+                Ok(Event::Eof) => {
+                    self.finished = true;
+                    None
+                }
+                Ok(_) => None,
+                Err(error) => {
+                    self.finished = true;
+                    Some(Err(LineDiagnostic::new(
+                        line_number,
+                        DiagnosticKind::UnreadableLine,
+                        error.to_string(),
+                    )))
+                }
+            };
+            self.count_lines();
+            if let Some(row) = row {
+                return Some((line_number, row));
+            }
+        }
+        None
+    }
+}
+
+/// Reads the post held by the attributes of a `row` element.
+///
+/// # Arguments
+///
+/// * `line_number` - The number of the line where the element starts, used in the diagnostic if it is invalid.
+/// * `element` - The `row` element.
+///
+/// # Returns
+///
+/// A result with the post, or the problem found in the element.
+fn read_post(line_number: u64, element: &BytesStart) -> Result<Post, LineDiagnostic> {
+    let malformed =
+        |message: String| LineDiagnostic::new(line_number, DiagnosticKind::MalformedLine, message);
+    let mut post_type_id = None;
+    let mut title = String::new();
+    let mut body = String::new();
+    let mut tags = None;
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|error| malformed(error.to_string()))?;
+        let key = attribute.key.as_ref();
+        if ![
+            POST_TYPE_ATTRIBUTE,
+            TITLE_ATTRIBUTE,
+            BODY_ATTRIBUTE,
+            TAGS_ATTRIBUTE,
+        ]
+        .contains(&key)
+        {
+            continue;
+        }
+        let value = attribute
+            .unescape_value()
+            .map_err(|error| malformed(error.to_string()))?
+            .into_owned();
+        match key {
+            POST_TYPE_ATTRIBUTE => post_type_id = Some(value),
+            TITLE_ATTRIBUTE => title = value,
+            BODY_ATTRIBUTE => body = value,
+            _ => tags = Some(value),
+        }
+    }
+    match post_type_id {
+        Some(post_type_id) => Ok(Post::new(post_type_id, title, body, tags)),
+        None => Err(malformed("missing attribute `PostTypeId`".to_string())),
+    }
+}
+
+/// Reads the site stored in a `Posts.xml` file of the official StackExchange data dump, which may be
+/// compressed. The rows are streamed by a single reader and their questions are processed in parallel,
+/// so the file is never held in memory. Rows that could not be used are skipped and reported in the
+/// returned diagnostics, with the line where they start. In strict mode, the rows after the first
+/// invalid row are not read.
+/// If the file could not be opened, it returns an error.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `site_name` - The name of the site.
+/// * `strict` - Whether the reading stops at the first invalid row.
+/// * `parser` - The parser that converts the questions of the posts.
+///
+/// # Returns
+///
+/// A result with the site, the diagnostics and the amount of lines of the file,
+/// or the error that prevented opening it.
+pub fn read_posts_xml(
+    path: &Path,
+    site_name: Option<String>,
+    strict: bool,
+    parser: &QuestionParser,
+) -> std::io::Result<(Site, FileDiagnostics, u64)> {
+    let file_path = path.display().to_string();
+    let mut rows = PostRows::new(open_file(path)?);
+    let stop = StrictStop::new(strict);
+    let empty = || {
+        (
+            parser.new_site(site_name.clone()),
+            FileDiagnostics::new(file_path.clone()),
+        )
+    };
+    let (site, diagnostics) = (&mut rows)
+        .take_while(|(line_number, _)| !stop.is_after(*line_number))
+        .par_bridge()
+        .fold(empty, |(mut site, mut diagnostics), (line_number, row)| {
+            if stop.is_after(line_number) {
+                return (site, diagnostics);
+            }
+            match row.map(|post| parser.parse_post(post, site_name.as_deref())) {
+                Ok(Some(question)) => site.add_question(question),
+                Ok(None) => {}
+                Err(error) => {
+                    stop.record(line_number);
+                    diagnostics.add_line_error(error)
+                }
+            }
+            (site, diagnostics)
+        })
+        .reduce(
+            empty,
+            |(mut site, mut diagnostics), (other, other_diagnostics)| {
+                // The line numbers are absolute, so the parts are merged without offsets.
+                site.merge(other);
+                diagnostics.merge(other_diagnostics);
+                (site, diagnostics)
+            },
+        );
+    Ok((site, diagnostics, rows.lines()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const POSTS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<posts>
+  <row Id="1" PostTypeId="1" Title="A title" Body="&lt;p&gt;Some body&lt;/p&gt;&#xA;" Tags="&lt;rust&gt;&lt;xml&gt;" />
+  <row Id="2" PostTypeId="2" ParentId="1" Body="&lt;p&gt;An answer&lt;/p&gt;" />
+  <row Id="3" Title="No type" />
+  <row Id="4" PostTypeId="1" Title="Pipes" Body="Body" Tags="|rust|" />
+</posts>
+"#;
+
+    #[test]
+    fn rows_are_read_with_their_lines() {
+        let mut rows = PostRows::new(Cursor::new(POSTS));
+        let lines: Vec<(u64, bool)> = (&mut rows)
+            .map(|(line, post)| (line, post.is_ok()))
+            .collect();
+        assert_eq!(lines, vec![(3, true), (4, true), (5, false), (6, true)]);
+        assert_eq!(rows.lines(), 7);
+    }
+
+    #[test]
+    fn only_the_questions_are_parsed() {
+        let parser = QuestionParser::default();
+        let questions: Vec<_> = PostRows::new(Cursor::new(POSTS))
+            .filter_map(|(_, post)| parser.parse_post(post.ok()?, None))
+            .collect();
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].words, 4);
+        assert_eq!(questions[0].tags, vec!["rust", "xml"]);
+        assert_eq!(questions[1].tags, vec!["rust"]);
+    }
+
+    #[test]
+    fn invalid_xml_ends_the_rows() {
+        let xml =
+            "<posts>\n<row PostTypeId=\"1\" Title=\"A\" />\n</post>\n<row PostTypeId=\"1\" />\n";
+        let rows: Vec<_> = PostRows::new(Cursor::new(xml)).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].1.is_ok());
+        assert!(matches!(
+            &rows[1],
+            (3, Err(error)) if error.kind == DiagnosticKind::UnreadableLine
+        ));
+    }
+}
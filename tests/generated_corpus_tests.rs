@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tp1_fork_join_108225::{
+        corpus_generator::{generate_corpus, site_name, CorpusConfig},
+        sites_information::sites_collection::SitesCollection,
+    };
+
+    fn corpus_directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tp1_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn analysis_counts_every_generated_question() {
+        let directory = corpus_directory("generated_corpus");
+        let config = CorpusConfig {
+            sites: 3,
+            questions_per_site: 400,
+            malformed_ratio: 0.05,
+            ..CorpusConfig::default()
+        };
+        let summary = generate_corpus(&config, &directory).unwrap();
+
+        let mut sites = SitesCollection::new();
+        let diagnostics = sites.load_sites(directory.to_str().unwrap()).unwrap();
+        let report = sites.generate_report("108225", 10, 10);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(diagnostics.skipped_lines(), summary.malformed_lines);
+        let mut questions = 0;
+        let mut words = 0;
+        for site in 0..config.sites {
+            let site_report = &report.sites[&site_name(site)];
+            questions += site_report.questions;
+            words += site_report.words;
+        }
+        assert_eq!(questions, summary.questions);
+        assert_eq!(words, summary.words);
+    }
+}
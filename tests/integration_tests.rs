@@ -1,26 +1,597 @@
 #[cfg(test)]
 mod tests {
     use tp1_fork_join_108225::sites_information::{
-        parsing_error::ParsingError, sites_collection::SitesCollection,
+        analysis_state::AnalysisState,
+        diagnostics::DiagnosticKind,
+        input_schema::InputSchema,
+        input_selection::InputSelection,
+        line_reader::{read_site, ReadStrategy},
+        parsing_error::ParsingError,
+        question_filter::QuestionFilter,
+        question_parser::QuestionParser,
+        ranking::{Ranking, RankingMetric, RankingOrder},
+        report::{Report, TagReport},
+        site_grouping::SiteGrouping,
+        site_id::DuplicateSites,
+        sites_collection::SitesCollection,
+        text_layout::TextLayout,
+        tokenizer::TokenizerKind,
     };
 
-    use serde_json::Value;
+    use std::{collections::BTreeMap, path::Path};
 
     #[test]
     fn we_can_load_one_site() {
         let mut sites = SitesCollection::new();
         let result = sites.load_sites("tests/testing_data/un_archivo");
         assert!(result.is_ok());
-        let parsed: Value = sites.generate_json_information("108225");
-        assert_eq!(parsed["padron"], "108225");
+        let report = sites.generate_report("108225", 10, 10);
+        assert_eq!(report.padron, "108225");
     }
 
     #[test]
     fn we_can_load_multiple_sites() {
         let mut sites = SitesCollection::new();
-        let result: Result<(), ParsingError> = sites.load_sites("tests/testing_data/dos_archivos");
+        let result = sites.load_sites("tests/testing_data/dos_archivos");
         assert!(result.is_ok());
-        let parsed: Value = sites.generate_json_information("108225");
-        assert_eq!(parsed["padron"], "108225");
+        let report = sites.generate_report("108225", 10, 10);
+        assert_eq!(report.padron, "108225");
+    }
+
+    #[test]
+    fn compressed_sites_give_the_same_information() {
+        let mut plain = SitesCollection::new();
+        assert!(plain.load_sites("tests/testing_data/un_archivo").is_ok());
+        let mut compressed = SitesCollection::new();
+        assert!(compressed
+            .load_sites("tests/testing_data/comprimido")
+            .is_ok());
+
+        let plain_report = plain.generate_report("108225", 10, 10);
+        let compressed_report = compressed.generate_report("108225", 10, 10);
+        let plain_site = &plain_report.sites["30_preguntas"];
+        let compressed_site = &compressed_report.sites["30_preguntas"];
+        assert_eq!(plain_site.questions, compressed_site.questions);
+        assert_eq!(plain_site.words, compressed_site.words);
+        assert_eq!(plain_site.tags, compressed_site.tags);
+        assert_eq!(plain_site.chatty_tags, compressed_site.chatty_tags);
+        assert_eq!(plain_report.tags, compressed_report.tags);
+    }
+
+    #[test]
+    fn reports_round_trip_from_disk() {
+        let mut sites = SitesCollection::new();
+        let diagnostics = sites.load_sites("tests/testing_data/con_errores").unwrap();
+        let mut report = sites.generate_report("108225", 10, 10);
+        report.diagnostics = Some(diagnostics.generate_report());
+
+        let path = std::env::temp_dir().join(format!("tp1_report_{}.json", std::process::id()));
+        std::fs::write(&path, report.to_json().unwrap()).unwrap();
+        let read = Report::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, report);
+    }
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        let mut sites = SitesCollection::new();
+        let diagnostics = sites.load_sites("tests/testing_data/con_errores").unwrap();
+
+        assert_eq!(diagnostics.skipped_files(), 0);
+        assert_eq!(diagnostics.skipped_lines(), 3);
+        let file = &diagnostics.files()[0];
+        assert!(file.file.ends_with("5_preguntas_3_errores.jsonl"));
+        let lines: Vec<(u64, DiagnosticKind)> = file
+            .errors
+            .iter()
+            .map(|error| (error.line, error.kind))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (3, DiagnosticKind::MalformedLine),
+                (5, DiagnosticKind::MalformedLine),
+                (7, DiagnosticKind::UnreadableLine),
+            ]
+        );
+
+        let report = sites.generate_report("108225", 10, 10);
+        assert_eq!(report.sites["5_preguntas_3_errores"].questions, 5);
+    }
+
+    #[test]
+    fn strict_mode_fails_on_malformed_lines() {
+        let mut sites = SitesCollection::new();
+        sites.set_strict_mode(true);
+        let result = sites.load_sites("tests/testing_data/con_errores");
+        assert!(matches!(
+            result,
+            Err(ParsingError::ErrorInvalidLine(_, _, _))
+        ));
+    }
+
+    #[test]
+    fn strict_mode_accepts_valid_files() {
+        let mut sites = SitesCollection::new();
+        sites.set_strict_mode(true);
+        let diagnostics = sites.load_sites("tests/testing_data/dos_archivos").unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn collections_use_their_own_thread_pools() {
+        let handles: Vec<_> = [1, 4]
+            .into_iter()
+            .map(|num_threads| {
+                std::thread::spawn(move || {
+                    let mut sites = SitesCollection::builder()
+                        .num_threads(num_threads)
+                        .build()
+                        .unwrap();
+                    assert_eq!(sites.thread_amount(), num_threads as usize);
+                    sites.load_sites("tests/testing_data/dos_archivos").unwrap();
+                    sites.generate_report("108225", 10, 10).tags
+                })
+            })
+            .collect();
+
+        let results: Vec<BTreeMap<String, TagReport>> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn reports_are_identical_for_any_amount_of_threads() {
+        let selection = InputSelection::new()
+            .directory("tests/testing_data")
+            .recursive(true);
+        let generate = |num_threads: u8, strategy: ReadStrategy| {
+            let mut sites = SitesCollection::builder()
+                .num_threads(num_threads)
+                .read_strategy(strategy)
+                .build()
+                .unwrap();
+            let diagnostics = sites.load_sites_from(&selection).unwrap();
+            let mut report = sites.generate_report("108225", 10, 10);
+            report.diagnostics = Some(diagnostics.generate_report());
+            report.to_json().unwrap()
+        };
+
+        let expected = generate(1, ReadStrategy::LineBridge);
+        for num_threads in [1, 2, 3, 8] {
+            for strategy in [ReadStrategy::LineBridge, ReadStrategy::Chunked(512)] {
+                assert_eq!(generate(num_threads, strategy), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn leaderboards_rank_the_same_run_by_several_metrics() {
+        let mut sites = SitesCollection::new();
+        sites.load_sites("tests/testing_data/dos_archivos").unwrap();
+        let rankings = vec![
+            (
+                "popular".to_string(),
+                Ranking::new(RankingMetric::Questions).limit(1),
+            ),
+            (
+                "least_verbose".to_string(),
+                Ranking::new(RankingMetric::Ratio)
+                    .order(RankingOrder::Ascending)
+                    .min_questions(80),
+            ),
+        ];
+        let leaderboards = sites.generate_leaderboards(&rankings);
+
+        assert_eq!(leaderboards["popular"].sites, vec!["100_preguntas"]);
+        assert_eq!(leaderboards["popular"].tags.len(), 1);
+        assert_eq!(leaderboards["least_verbose"].sites, vec!["100_preguntas"]);
+        assert!(leaderboards["least_verbose"].tags.is_empty());
+    }
+
+    #[test]
+    fn empty_and_malformed_sites_are_flagged_and_not_ranked() {
+        let mut sites = SitesCollection::new();
+        let diagnostics = sites
+            .load_sites("tests/testing_data/sin_preguntas")
+            .unwrap();
+        assert_eq!(diagnostics.skipped_lines(), 3);
+        assert_eq!(sites.calculate_words_questions_ratio(), None);
+
+        let rankings = vec![(
+            "popular".to_string(),
+            Ranking::new(RankingMetric::Questions),
+        )];
+        let mut report = sites.generate_report("108225", 10, 10);
+        report.leaderboards = sites.generate_leaderboards(&rankings);
+
+        assert_eq!(report.sites["vacio"].questions, 0);
+        assert_eq!(report.sites["3_errores"].questions, 0);
+        assert!(report.totals.chatty_sites.is_empty());
+        assert!(report.leaderboards["popular"].sites.is_empty());
+        assert_eq!(report.totals.empty_sites, vec!["3_errores", "vacio"]);
+    }
+
+    #[test]
+    fn empty_sites_do_not_affect_the_valid_ones() {
+        let selection = InputSelection::new()
+            .directory("tests/testing_data/sin_preguntas")
+            .directory("tests/testing_data/un_archivo");
+        let mut sites = SitesCollection::new();
+        sites.load_sites_from(&selection).unwrap();
+        let report = sites.generate_report("108225", 10, 10);
+
+        assert_eq!(report.totals.chatty_sites, vec!["30_preguntas"]);
+        assert_eq!(report.totals.empty_sites.len(), 2);
+        assert!(Report::from_json(&report.to_json().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn related_tags_of_all_the_sites_merge_the_sites() {
+        let mut sites = SitesCollection::builder()
+            .num_threads(2)
+            .tag_pairs(true)
+            .build()
+            .unwrap();
+        sites.load_sites("tests/testing_data/dos_archivos").unwrap();
+        let related = sites.generate_related_tags_report(3);
+        let all = sites.get_all_co_occurrences();
+
+        let mut parser = QuestionParser::default();
+        parser.set_tag_pairs(true);
+        let per_site: Vec<_> = ["100_preguntas.jsonl", "70_preguntas.jsonl"]
+            .iter()
+            .map(|file| {
+                let path = Path::new("tests/testing_data/dos_archivos").join(file);
+                let (site, _, _) =
+                    read_site(&path, None, ReadStrategy::LineBridge, false, &parser).unwrap();
+                site.get_tag_pairs().unwrap().clone()
+            })
+            .collect();
+        let sum = |tag: &str, other: &str| -> u64 {
+            per_site.iter().map(|pairs| pairs.count(tag, other)).sum()
+        };
+
+        for (tag, other, questions) in [
+            ("python", "python-3.x", 10),
+            ("bash", "shell", 4),
+            ("shell", "bash", 4),
+            ("bash", "python", 2),
+            ("bash", "shell-script", 3),
+        ] {
+            assert_eq!(all.count(tag, other), sum(tag, other));
+            assert_eq!(all.count(tag, other), questions);
+        }
+        assert_eq!(related.sites.len(), 2);
+        for (tag, related_tags) in &related.tags {
+            assert!(related_tags.len() <= 3);
+            for related_tag in related_tags {
+                assert_eq!(sum(tag, &related_tag.tag), related_tag.questions);
+                assert_eq!(all.count(tag, &related_tag.tag), related_tag.questions);
+            }
+        }
+    }
+
+    #[test]
+    fn distributions_summarize_sites_and_tags() {
+        let mut sites = SitesCollection::new();
+        sites.load_sites("tests/testing_data/un_archivo").unwrap();
+        let report = sites.generate_report("108225", 10, 10);
+        let site = &report.sites["30_preguntas"];
+        let distribution = site.distribution.as_ref().unwrap();
+
+        assert!(distribution.min as f64 <= distribution.median);
+        assert!(distribution.median <= distribution.p90);
+        assert!(distribution.p90 <= distribution.p99);
+        for tag in site.tags.values() {
+            let tag_distribution = tag.distribution.as_ref().unwrap();
+            assert!(tag_distribution.min >= distribution.min);
+            assert!(tag_distribution.max <= distribution.max);
+        }
+    }
+
+    #[test]
+    fn titles_and_bodies_follow_the_text_layout() {
+        let report_with = |layout: &str| {
+            let mut sites = SitesCollection::builder()
+                .text_layout(TextLayout::from_spec(layout).unwrap())
+                .build()
+                .unwrap();
+            sites.load_sites("tests/testing_data/un_archivo").unwrap();
+            sites.generate_report("108225", 10, 10).sites["30_preguntas"].clone()
+        };
+
+        let default = report_with("title,body");
+        assert!(default.fields.title_words > 0);
+        assert_eq!(
+            default.fields.title_words + default.fields.body_words,
+            default.words
+        );
+
+        let swapped = report_with("body,title");
+        assert_eq!(swapped.fields.title_words, default.fields.body_words);
+        assert_eq!(swapped.words, default.words);
+
+        let bodies = report_with("body");
+        assert_eq!(bodies.fields.title_words, 0);
+        assert_eq!(bodies.fields.body_words, default.words);
+    }
+
+    #[test]
+    fn tokenizers_count_the_fixtures_differently() {
+        let words_with = |tokenizer: TokenizerKind, directory: &str| {
+            let mut sites = SitesCollection::builder()
+                .tokenizer(tokenizer.tokenizer())
+                .build()
+                .unwrap();
+            sites.load_sites(directory).unwrap();
+            let report = sites.generate_report("108225", 10, 10);
+            report
+                .sites
+                .values()
+                .map(|site| site.words)
+                .collect::<Vec<u64>>()
+        };
+
+        let code = "tests/testing_data/con_codigo";
+        assert_eq!(words_with(TokenizerKind::Whitespace, code), vec![48]);
+        assert_eq!(words_with(TokenizerKind::Unicode, code), vec![57]);
+        assert_eq!(words_with(TokenizerKind::Prose, code), vec![34]);
+
+        let plain = "tests/testing_data/dos_archivos";
+        let unicode = words_with(TokenizerKind::Unicode, plain);
+        let prose = words_with(TokenizerKind::Prose, plain);
+        assert_ne!(words_with(TokenizerKind::Whitespace, plain), unicode);
+        for (prose, unicode) in prose.iter().zip(&unicode) {
+            assert!(prose <= unicode);
+        }
+    }
+
+    #[test]
+    fn thread_amount_can_be_set_more_than_once() {
+        let mut sites = SitesCollection::new();
+        assert!(sites.set_thread_amount(2).is_ok());
+        assert!(sites.set_thread_amount(3).is_ok());
+        assert_eq!(sites.thread_amount(), 3);
+        assert!(sites.load_sites("tests/testing_data/un_archivo").is_ok());
+    }
+
+    #[test]
+    fn incremental_analyses_only_read_the_changed_files() {
+        let directory =
+            std::env::temp_dir().join(format!("tp1_incremental_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for file in ["100_preguntas.jsonl", "70_preguntas.jsonl"] {
+            let contents =
+                std::fs::read_to_string(format!("tests/testing_data/dos_archivos/{}", file))
+                    .unwrap();
+            std::fs::write(directory.join(file), contents + "\n").unwrap();
+        }
+        let selection = InputSelection::new().directory(&directory);
+        let full_report = || {
+            let mut sites = SitesCollection::new();
+            sites.load_sites_from(&selection).unwrap();
+            sites.generate_report("108225", 10, 10)
+        };
+        let incremental = |state: Option<&AnalysisState>| {
+            let mut sites = SitesCollection::new();
+            let load = sites.load_sites_incrementally(&selection, state).unwrap();
+            let counts = (load.unchanged, load.appended, load.processed);
+            (sites.generate_report("108225", 10, 10), load.state, counts)
+        };
+
+        let (report, state, counts) = incremental(None);
+        assert_eq!(counts, (0, 0, 2));
+        assert_eq!(report, full_report());
+
+        let state_path = directory.with_extension("state.json");
+        state.save(&state_path).unwrap();
+        let state = AnalysisState::load(&state_path).unwrap();
+        std::fs::remove_file(&state_path).unwrap();
+        let (report, state, counts) = incremental(Some(&state));
+        assert_eq!(counts, (2, 0, 0));
+        assert_eq!(report, full_report());
+
+        let appended =
+            std::fs::read_to_string("tests/testing_data/un_archivo/30_preguntas.jsonl").unwrap();
+        let mut contents = std::fs::read_to_string(directory.join("70_preguntas.jsonl")).unwrap();
+        contents.push_str(&appended);
+        contents.push_str("\nnot a question\n");
+        std::fs::write(directory.join("70_preguntas.jsonl"), contents).unwrap();
+        let (report, state, counts) = incremental(Some(&state));
+        assert_eq!(counts, (1, 1, 0));
+        assert_eq!(report, full_report());
+        assert_eq!(report.sites["70_preguntas"].questions, 100);
+        let errors = &state.files.values().last().unwrap().diagnostics.errors;
+        assert_eq!(errors[0].line, 101);
+
+        std::fs::write(directory.join("100_preguntas.jsonl"), appended).unwrap();
+        let (report, state, counts) = incremental(Some(&state));
+        assert_eq!(counts, (1, 0, 1));
+        assert_eq!(report, full_report());
+
+        let mut sites = SitesCollection::builder()
+            .tokenizer(TokenizerKind::Prose.tokenizer())
+            .build()
+            .unwrap();
+        let load = sites
+            .load_sites_incrementally(&selection, Some(&state))
+            .unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!((load.unchanged, load.appended, load.processed), (0, 0, 2));
+    }
+
+    #[test]
+    fn files_of_the_same_site_are_merged_or_rejected() {
+        let selection = InputSelection::new()
+            .directory("tests/testing_data/un_archivo")
+            .directory("tests/testing_data/comprimido");
+
+        let mut sites = SitesCollection::new();
+        let diagnostics = sites.load_sites_from(&selection).unwrap();
+        let report = sites.generate_report("108225", 10, 10);
+        assert_eq!(report.sites.len(), 1);
+        assert_eq!(report.sites["30_preguntas"].questions, 60);
+        let merged = &diagnostics.merged_sites()["30_preguntas"];
+        assert_eq!(merged.len(), 2);
+        assert!(diagnostics.is_empty());
+
+        let mut sites = SitesCollection::builder()
+            .duplicate_sites(DuplicateSites::Error)
+            .build()
+            .unwrap();
+        match sites.load_sites_from(&selection) {
+            Err(ParsingError::ErrorDuplicateSite(site, first, second)) => {
+                assert_eq!(site, "30_preguntas");
+                assert_ne!(first, second);
+            }
+            other => panic!("expected a duplicate site, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn shards_are_grouped_into_their_site() {
+        let mut whole = SitesCollection::new();
+        whole.load_sites("tests/testing_data/un_archivo").unwrap();
+        let whole = whole.generate_report("108225", 10, 10);
+
+        let mut separate = SitesCollection::new();
+        separate
+            .load_sites("tests/testing_data/fragmentado")
+            .unwrap();
+        let separate = separate.generate_report("108225", 10, 10);
+        assert_eq!(
+            separate.sites.keys().collect::<Vec<&String>>(),
+            vec!["30_preguntas.part1", "30_preguntas.part2"]
+        );
+
+        let mapping = std::env::temp_dir().join(format!("tp1_mapping_{}.json", std::process::id()));
+        std::fs::write(
+            &mapping,
+            r#"{"30_preguntas.part1.jsonl": "30_preguntas", "tests/testing_data/fragmentado/30_preguntas.part2.jsonl": "30_preguntas"}"#,
+        )
+        .unwrap();
+        let mapped = SiteGrouping::from_mapping_file(&mapping);
+        std::fs::remove_file(&mapping).unwrap();
+
+        for grouping in [
+            SiteGrouping::pattern(r"^(.*)\.part\d+").unwrap(),
+            mapped.unwrap(),
+        ] {
+            let mut sites = SitesCollection::builder()
+                .site_grouping(grouping)
+                .duplicate_sites(DuplicateSites::Error)
+                .build()
+                .unwrap();
+            let diagnostics = sites.load_sites("tests/testing_data/fragmentado").unwrap();
+            let report = sites.generate_report("108225", 10, 10);
+            assert_eq!(report.sites, whole.sites);
+            assert_eq!(report.tags, whole.tags);
+            assert_eq!(diagnostics.merged_sites()["30_preguntas"].len(), 2);
+        }
+    }
+
+    #[test]
+    fn every_schema_reads_its_fixture() {
+        let site_with = |schema: InputSchema, directory: &str| {
+            let mut sites = SitesCollection::builder()
+                .input_schema(schema)
+                .build()
+                .unwrap();
+            let diagnostics = sites.load_sites(directory).unwrap();
+            assert!(diagnostics.is_empty());
+            let report = sites.generate_report("108225", 10, 10);
+            report.sites.into_values().next().unwrap()
+        };
+
+        let texts = site_with(InputSchema::Texts, "tests/testing_data/con_codigo");
+        let fields = site_with(InputSchema::Fields, "tests/testing_data/con_campos");
+        let posts = site_with(InputSchema::Posts, "tests/testing_data/con_posts");
+
+        for site in [&fields, &posts] {
+            assert_eq!(site.questions, texts.questions);
+            assert_eq!(site.fields.title_words, texts.fields.title_words);
+            assert_eq!(site.fields.body_words, texts.fields.body_words);
+            for (tag, report) in &texts.tags {
+                assert_eq!(site.tags[tag].questions, report.questions);
+            }
+        }
+        assert_eq!(posts.tags, texts.tags);
+        assert_eq!(posts.words, 48);
+        assert_eq!(fields.words, 48 + 9);
+        assert_eq!(fields.tags["rust"].words, 48 + 9);
+
+        let mut sites = SitesCollection::new();
+        let diagnostics = sites.load_sites("tests/testing_data/con_posts").unwrap();
+        assert_eq!(diagnostics.skipped_lines(), 6);
+    }
+
+    #[test]
+    fn posts_xml_dumps_are_read_like_posts_lines() {
+        let mut xml = SitesCollection::new();
+        let diagnostics = xml.load_sites("tests/testing_data/con_xml").unwrap();
+        assert!(diagnostics.is_empty());
+        let mut lines = SitesCollection::builder()
+            .input_schema(InputSchema::Posts)
+            .build()
+            .unwrap();
+        lines.load_sites("tests/testing_data/con_posts").unwrap();
+
+        let xml_report = xml.generate_report("108225", 10, 10);
+        let lines_report = lines.generate_report("108225", 10, 10);
+        assert_eq!(
+            xml_report.totals.chatty_tags,
+            lines_report.totals.chatty_tags
+        );
+        assert_eq!(xml_report.tags, lines_report.tags);
+        let site = &xml_report.sites["rust.stackexchange.com"];
+        assert_eq!(site, lines_report.sites.values().next().unwrap());
+        assert_eq!(site.questions, 3);
+        // The sibling Users.xml of the dump is not read as a site.
+        assert_eq!(xml_report.sites.len(), 1);
+        assert!(xml_report.totals.empty_sites.is_empty());
+
+        let mut strict = SitesCollection::builder().strict(true).build().unwrap();
+        assert!(strict.load_sites("tests/testing_data/con_xml").is_ok());
+    }
+
+    #[test]
+    fn filters_restrict_the_aggregated_questions() {
+        let report_with = |expression: &str, directory: &str| {
+            let mut sites = SitesCollection::builder()
+                .question_filter(Some(QuestionFilter::parse(expression).unwrap()))
+                .build()
+                .unwrap();
+            sites.load_sites(directory).unwrap();
+            sites.generate_report("108225", 10, 10)
+        };
+        let site_of = |report: Report| report.sites.into_values().next().unwrap();
+
+        let rayon = site_of(report_with("tag:rayon", "tests/testing_data/con_codigo"));
+        let others = site_of(report_with(
+            "not tag:rayon",
+            "tests/testing_data/con_codigo",
+        ));
+        assert_eq!((rayon.questions, others.questions), (1, 2));
+        assert_eq!(rayon.words + others.words, 48);
+        assert!(!others.tags.contains_key("rayon"));
+
+        let by_text = site_of(report_with(
+            r#"tag:rust title:"par_iter" words<20"#,
+            "tests/testing_data/con_codigo",
+        ));
+        assert_eq!(by_text, rayon);
+
+        // The files of the sites rejected by the site predicates are not read.
+        let report = report_with("site:70_*", "tests/testing_data/dos_archivos");
+        assert_eq!(report.sites["70_preguntas"].questions, 70);
+        assert!(!report.sites.contains_key("100_preguntas"));
+        assert!(report.totals.empty_sites.is_empty());
+        let report = report_with("site:70_* and tag:rayon", "tests/testing_data/dos_archivos");
+        assert_eq!(report.sites.len(), 1);
+        assert_eq!(report.totals.empty_sites, vec!["70_preguntas"]);
     }
 }